@@ -44,7 +44,7 @@ pub fn part_one(input: &str) -> Option<u32> {
                     game.split(',').any(|draw| {
                         // match the regex against the draw
                         let Some(captures) = re.captures(draw) else {
-                            println!("no captures found in Game {}", id);
+                            advent_of_code::debug!("no captures found in Game {}", id);
                             // if no captures were found,
                             // we assume the game was invalid
                             return true;
@@ -53,7 +53,7 @@ pub fn part_one(input: &str) -> Option<u32> {
                         // try to parse the amount,
                         // if it fails we assume the game was invalid
                         let Ok(amount) = &captures["amount"].parse::<u32>() else {
-                            println!("no color amount found in Game {}", id);
+                            advent_of_code::debug!("no color amount found in Game {}", id);
                             return true;
                         };
 
@@ -31,8 +31,8 @@ pub fn part_one(input: &str) -> Option<u32> {
             .map(|m| (m.start(), m.end()))
             // loop over all matches and filter those that have adjacent symbols
             .filter(|(s, e)| {
-                let start = s.clone();
-                let end = e.clone();
+                let start = *s;
+                let end = *e;
 
                 // check if there are symbols to the left of our string
                 if start > 0 && subslice_has_symbol(line, start - 1, start) {
@@ -85,7 +85,7 @@ fn adjacent_numbers_in_line(line: &str, pos: usize) -> Vec<u32> {
     NUMBER_REGEX
         .find_iter(line)
         .map(|m| (m.start(), m.end()))
-        .filter(|(m_start, m_end)| pos_start <= m_end.clone() - 1 && m_start.clone() <= pos_end)
+        .filter(|(m_start, m_end)| pos_start < *m_end && *m_start <= pos_end)
         .map(|(start, end)| line[start..end].parse().unwrap())
         .collect()
 }
@@ -103,7 +103,7 @@ pub fn part_two(input: &str) -> Option<u32> {
             .match_indices('*')
             // loop over all matches and filter those that have two adjacent numbers
             .filter_map(|(p, _)| {
-                let position = p.clone();
+                let position = p;
 
                 let mut adjacent_numbers: Vec<u32> = Vec::new();
 
@@ -1,4 +1,6 @@
-advent_of_code::solution!(4);
+advent_of_code::solution!(4, generator = generate);
+
+use advent_of_code::template::rng::Rng;
 
 pub fn part_one(input: &str) -> Option<u32> {
     // split the input into individual cards
@@ -123,6 +125,44 @@ pub fn part_two(input: &str) -> Option<u32> {
     Some(total)
 }
 
+// generate `10 * scale` cards for stress benchmarks.
+// cards only win copies of cards in their block of ten,
+// so the total number of copies grows linearly with the scale.
+pub fn generate(rng: &mut Rng, scale: usize) -> String {
+    let format = |numbers: &[u64]| {
+        numbers
+            .iter()
+            .map(|n| format!("{n:>2}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    (1..=scale * 10)
+        .map(|id| {
+            let cards_left_in_block = 9 - (id - 1) % 10;
+            let matches = rng.range(0, cards_left_in_block.min(5) as u64 + 1) as usize;
+
+            // draw 13 distinct numbers, the first 5 of them are the winning numbers
+            let mut pool: Vec<u64> = (1..100).collect();
+            let drawn: Vec<u64> = (0..13)
+                .map(|_| pool.swap_remove(rng.range(0, pool.len() as u64) as usize))
+                .collect();
+
+            let numbers: Vec<u64> = drawn[..matches]
+                .iter()
+                .chain(&drawn[5..13 - matches])
+                .copied()
+                .collect();
+
+            format!(
+                "Card {id:>3}: {} | {}\n",
+                format(&drawn[..5]),
+                format(&numbers)
+            )
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,6 +1,8 @@
 use rayon::prelude::*;
 
-advent_of_code::solution!(5);
+advent_of_code::solution!(5, generator = generate);
+
+use advent_of_code::template::rng::Rng;
 
 struct Map {
     source: u64,
@@ -69,7 +71,7 @@ pub fn part_one(input: &str) -> Option<u32> {
     // read the first line to get the initial source id's
     let mut ids: Vec<u64> = input_iter
         // take the first row
-        .nth(0)
+        .next()
         // if there is none, the input is not valid
         .unwrap()
         // remove the string 'seeds'
@@ -119,7 +121,7 @@ pub fn part_two(input: &str) -> Option<u32> {
     // read the first line to get the initial source id's
     let identifier: Vec<u64> = input_iter
         // take the first row
-        .nth(0)
+        .next()
         // if there is none, the input is not valid
         .unwrap()
         // remove the string 'seeds'
@@ -135,7 +137,7 @@ pub fn part_two(input: &str) -> Option<u32> {
         .par_iter()
         .chunks(2)
         .map(|chunk| {
-            let start = chunk.first().unwrap().clone();
+            let start = *chunk.first().unwrap();
             let length = chunk.last().unwrap();
             let stop = *start + *length;
 
@@ -176,6 +178,41 @@ pub fn part_two(input: &str) -> Option<u32> {
     ids.iter().min().map(|x| *x as u32)
 }
 
+const MAPPINGS: [&str; 7] = [
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+
+// generate an input for stress benchmarks with `10 * scale` seed ranges of 100 seeds each,
+// mapped through a handful of random ranges
+pub fn generate(rng: &mut Rng, scale: usize) -> String {
+    let seeds: Vec<String> = (0..10 * scale)
+        .map(|_| format!("{} {}", rng.range(0, 1_000_000_000), 100))
+        .collect();
+
+    let mut input = format!("seeds: {}\n", seeds.join(" "));
+
+    for name in MAPPINGS {
+        input += &format!("\n{name} map:\n");
+
+        for _ in 0..rng.range(3, 8) {
+            input += &format!(
+                "{} {} {}\n",
+                rng.range(0, 1_000_000_000),
+                rng.range(0, 1_000_000_000),
+                rng.range(1, 100_000_000)
+            );
+        }
+    }
+
+    input
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
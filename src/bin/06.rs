@@ -1,5 +1,3 @@
-use std::u64;
-
 advent_of_code::solution!(6);
 
 // acceleration in meters / s * s
@@ -31,19 +29,17 @@ pub fn part_one(input: &str) -> Option<u32> {
 
     let times: Vec<u64> = time_line
         .replace("Time:", "")
-        .trim()
         .split_whitespace()
         .map(|x| x.parse().unwrap())
         .collect();
 
     let distances: Vec<u64> = distances_line
         .replace("Distance:", "")
-        .trim()
         .split_whitespace()
         .map(|x| x.parse().unwrap())
         .collect();
 
-    let races: Vec<(u64, u64)> = times.into_iter().zip(distances.into_iter()).collect();
+    let races: Vec<(u64, u64)> = times.into_iter().zip(distances).collect();
 
     let product: u64 = races
         .into_iter()
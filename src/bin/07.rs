@@ -42,10 +42,7 @@ mod p1 {
                 "K" => Self::King,
                 "A" => Self::Ace,
                 _ => {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        "Invalid card",
-                    ));
+                    return Err(std::io::Error::other("Invalid card"));
                 }
             };
 
@@ -219,10 +216,7 @@ mod p2 {
                 "K" => Self::King,
                 "A" => Self::Ace,
                 _ => {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        "Invalid card",
-                    ));
+                    return Err(std::io::Error::other("Invalid card"));
                 }
             };
 
@@ -1,8 +1,4 @@
-use std::{
-    collections::HashMap,
-    io::{Error, ErrorKind},
-    str::FromStr,
-};
+use std::{collections::HashMap, io::Error, str::FromStr};
 
 advent_of_code::solution!(8);
 
@@ -18,7 +14,7 @@ impl FromStr for Instruction {
         match s.to_uppercase().as_str() {
             "L" => Ok(Self::Left),
             "R" => Ok(Self::Right),
-            _ => Err(Error::new(ErrorKind::Other, "unknown instruction")),
+            _ => Err(Error::other("unknown instruction")),
         }
     }
 }
@@ -35,7 +31,7 @@ impl FromStr for Map {
             let mut split = line.split('=');
 
             let source_str = split.next().unwrap().trim();
-            let remainder = split.next().unwrap().replace('(', "").replace(')', "");
+            let remainder = split.next().unwrap().replace(['(', ')'], "");
 
             let mut destination_split = remainder.split(',');
 
@@ -92,7 +88,7 @@ pub fn part_one(input: &str) -> Option<u32> {
     Some(steps)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(_input: &str) -> Option<u32> {
     None
 }
 
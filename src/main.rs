@@ -1,9 +1,16 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve};
+use advent_of_code::template::{
+    commands::{all, completions, download, gen, read, scaffold, solve},
+    config,
+};
 use args::{parse, AppArguments};
 
 mod args {
-    use advent_of_code::template::Day;
-    use std::process;
+    use advent_of_code::template::{
+        baseline, cli,
+        config::{self, BenchSettings},
+        export, generate, log, scaling, visualize, Day,
+    };
+    use std::{ffi::OsString, path::PathBuf, process};
 
     pub enum AppArguments {
         Download {
@@ -20,48 +27,143 @@ mod args {
             day: Day,
             release: bool,
             time: bool,
+            bench: BenchSettings,
+            threads: Option<usize>,
+            scaling: bool,
             dhat: bool,
+            profile_cpu: bool,
+            visualize: Option<visualize::Backend>,
+            verbose: u8,
             submit: Option<u8>,
+            watch: bool,
+            test: bool,
+            input: Option<String>,
+            generated: Option<usize>,
+            variant: Option<String>,
+            compare: bool,
+        },
+        Gen {
+            day: Day,
+            scale: usize,
         },
         All {
             release: bool,
             time: bool,
+            bench: BenchSettings,
+            threads: Option<usize>,
+            dhat: bool,
+            report: Option<PathBuf>,
+            export: Option<(export::Format, PathBuf)>,
+            import: Option<PathBuf>,
+            baseline: Option<String>,
+            threshold: f64,
+            save_baseline: Option<String>,
+        },
+        Completions {
+            shell: String,
         },
     }
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
-        let mut args = pico_args::Arguments::from_env();
+        parse_from(std::env::args().skip(1).collect())
+    }
+
+    fn parse_from(raw_args: Vec<String>) -> Result<AppArguments, Box<dyn std::error::Error>> {
+        let mut args =
+            pico_args::Arguments::from_vec(raw_args.iter().map(OsString::from).collect());
+
+        let Some(name) = args.subcommand()? else {
+            eprintln!("{}", cli::help());
+            process::exit(1);
+        };
+
+        if matches!(name.as_str(), "help" | "--help" | "-h") {
+            print_help(args.opt_free_from_str::<String>()?.as_deref());
+        }
+
+        let Some(command) = cli::find(&name) else {
+            eprintln!("Error: unknown command `{name}`");
+            if let Some(suggestion) = cli::suggest(&name, cli::COMMANDS.iter().map(|c| c.name)) {
+                eprintln!("\n  did you mean `{suggestion}`?");
+            }
+            eprintln!("\nRun `cargo run -- help` to list all commands.");
+            process::exit(1);
+        };
+
+        if args.contains(["-h", "--help"]) {
+            print_help(Some(command.name));
+        }
+
+        if let Err(message) = command.validate(&raw_args[1..]) {
+            eprintln!("Error: {message}");
+            process::exit(1);
+        }
 
-        let app_args = match args.subcommand()?.as_deref() {
-            Some("all") => AppArguments::All {
+        // NOTE: free arguments are parsed last, pico-args takes the first remaining argument.
+        let app_args = match command.name {
+            "all" => AppArguments::All {
                 release: args.contains("--release"),
                 time: args.contains("--time"),
+                bench: config::get().bench.with_args(&mut args)?,
+                threads: args.opt_value_from_fn("--threads", scaling::parse_threads)?,
+                dhat: args.contains("--dhat"),
+                report: args.opt_value_from_str("--report")?,
+                export: match (
+                    args.opt_value_from_str("--export")?,
+                    args.opt_value_from_str("--export-path")?,
+                ) {
+                    (Some(format), Some(path)) => Some((format, path)),
+                    (None, None) => None,
+                    _ => {
+                        return Err("`--export` and `--export-path` must be passed together.".into())
+                    }
+                },
+                import: args.opt_value_from_str("--import")?,
+                baseline: args.opt_value_from_str("--baseline")?,
+                threshold: args
+                    .opt_value_from_fn("--threshold", baseline::parse_threshold)?
+                    .unwrap_or(baseline::DEFAULT_THRESHOLD),
+                save_baseline: args.opt_value_from_str("--save-baseline")?,
             },
-            Some("download") => AppArguments::Download {
-                day: args.free_from_str()?,
+            "download" => AppArguments::Download {
+                day: args.free_from_fn(cli::parse_day)?,
             },
-            Some("read") => AppArguments::Read {
-                day: args.free_from_str()?,
+            "read" => AppArguments::Read {
+                day: args.free_from_fn(cli::parse_day)?,
             },
-            Some("scaffold") => AppArguments::Scaffold {
-                day: args.free_from_str()?,
+            "scaffold" => AppArguments::Scaffold {
                 download: args.contains("--download"),
+                day: args.free_from_fn(cli::parse_day)?,
             },
-            Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
+            "solve" => AppArguments::Solve {
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 time: args.contains("--time"),
+                bench: config::get().bench.with_args(&mut args)?,
+                threads: args.opt_value_from_fn("--threads", scaling::parse_threads)?,
+                scaling: args.contains("--scaling"),
                 dhat: args.contains("--dhat"),
+                profile_cpu: args.contains("--profile-cpu"),
+                visualize: visualize::Backend::with_args(&mut args)?,
+                verbose: log::parse_verbosity(&mut args),
+                watch: args.contains("--watch"),
+                test: args.contains("--test"),
+                input: args.opt_value_from_str("--input")?,
+                generated: args.opt_value_from_fn("--generated", generate::parse_scale)?,
+                variant: args.opt_value_from_str("--variant")?,
+                compare: args.contains("--compare"),
+                day: args.free_from_fn(cli::parse_day)?,
             },
-            Some(x) => {
-                eprintln!("Unknown command: {x}");
-                process::exit(1);
-            }
-            None => {
-                eprintln!("No command specified.");
-                process::exit(1);
-            }
+            "gen" => AppArguments::Gen {
+                scale: args
+                    .opt_value_from_fn("--scale", generate::parse_scale)?
+                    .unwrap_or(1),
+                day: args.free_from_fn(cli::parse_day)?,
+            },
+            "completions" => AppArguments::Completions {
+                shell: args.free_from_str()?,
+            },
+            _ => unreachable!("command `{name}` is not handled"),
         };
 
         let remaining = args.finish();
@@ -71,31 +173,155 @@ mod args {
 
         Ok(app_args)
     }
+
+    fn print_help(command: Option<&str>) -> ! {
+        match command.map(|name| (name, cli::find(name))) {
+            None => println!("{}", cli::help()),
+            Some((_, Some(command))) => println!("{}", command.help()),
+            Some((name, None)) => {
+                eprintln!("Error: unknown command `{name}`");
+                process::exit(1);
+            }
+        }
+        process::exit(0);
+    }
+
+    #[cfg(feature = "test_lib")]
+    mod tests {
+        use super::{parse_from, AppArguments};
+        use advent_of_code::{day, template::export::Format};
+        use std::path::PathBuf;
+
+        fn parse_args(args: &str) -> AppArguments {
+            parse_from(args.split(' ').map(String::from).collect()).unwrap()
+        }
+
+        #[test]
+        fn parses_export_before_other_options() {
+            let AppArguments::All { export, import, .. } =
+                parse_args("all --time --export json --export-path out.json --import in.csv")
+            else {
+                panic!("expected the all command");
+            };
+
+            assert_eq!(export, Some((Format::Json, PathBuf::from("out.json"))));
+            assert_eq!(import, Some(PathBuf::from("in.csv")));
+        }
+
+        #[test]
+        fn parses_inline_values() {
+            let AppArguments::Gen { day, scale } = parse_args("gen --scale=2 4") else {
+                panic!("expected the gen command");
+            };
+            assert_eq!((day, scale), (day!(4), 2));
+
+            let AppArguments::Solve { submit, input, .. } =
+                parse_args("solve --submit=1 --input=--weird.txt 1")
+            else {
+                panic!("expected the solve command");
+            };
+            assert_eq!(submit, Some(1));
+            assert_eq!(input.as_deref(), Some("--weird.txt"));
+        }
+
+        #[test]
+        fn requires_export_format_and_path() {
+            assert!(parse_from(vec!["all".into(), "--export".into(), "csv".into()]).is_err());
+            assert!(
+                parse_from(vec!["all".into(), "--export-path".into(), "out.csv".into()]).is_err()
+            );
+        }
+    }
 }
 
 fn main() {
+    // reports an invalid configuration before anything is printed.
+    let config = config::get();
+
     match parse() {
         Err(err) => {
             eprintln!("Error: {err}");
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release, time } => all::handle(release, time),
-            AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
+            AppArguments::All {
+                release,
+                time,
+                bench,
+                threads,
+                dhat,
+                report,
+                export,
+                import,
+                baseline,
+                threshold,
+                save_baseline,
+            } => all::handle(
+                &all::Options {
+                    release,
+                    time,
+                    bench,
+                    threads,
+                    dhat,
+                    report,
+                    export,
+                    import,
+                    baseline,
+                    threshold,
+                    save_baseline,
+                },
+                config,
+            ),
+            AppArguments::Download { day } => download::handle(day, config),
+            AppArguments::Read { day } => read::handle(day, config),
             AppArguments::Scaffold { day, download } => {
-                scaffold::handle(day);
+                scaffold::handle(day, config);
                 if download {
-                    download::handle(day);
+                    download::handle(day, config);
                 }
             }
             AppArguments::Solve {
                 day,
                 release,
                 time,
+                bench,
+                threads,
+                scaling,
                 dhat,
+                profile_cpu,
+                visualize,
+                verbose,
                 submit,
-            } => solve::handle(day, release, time, dhat, submit),
+                watch,
+                test,
+                input,
+                generated,
+                variant,
+                compare,
+            } => solve::handle(
+                day,
+                solve::Options {
+                    release,
+                    time,
+                    bench,
+                    threads,
+                    scaling,
+                    dhat,
+                    profile_cpu,
+                    visualize,
+                    verbose,
+                    submit,
+                    watch,
+                    test,
+                    input,
+                    generated,
+                    variant,
+                    compare,
+                },
+                config,
+            ),
+            AppArguments::Gen { day, scale } => gen::handle(day, scale, config),
+            AppArguments::Completions { shell } => completions::handle(&shell),
         },
     };
 }
@@ -4,7 +4,7 @@ use std::{
     process::{Command, Output, Stdio},
 };
 
-use crate::template::Day;
+use crate::template::{config::Config, Day};
 
 #[derive(Debug)]
 pub enum AocCommandError {
@@ -35,8 +35,8 @@ pub fn check() -> Result<(), AocCommandError> {
     Ok(())
 }
 
-pub fn read(day: Day) -> Result<Output, AocCommandError> {
-    let puzzle_path = get_puzzle_path(day);
+pub fn read(day: Day, config: &Config) -> Result<Output, AocCommandError> {
+    let puzzle_path = config.puzzle_path(day).display().to_string();
 
     let args = build_args(
         "read",
@@ -46,14 +46,15 @@ pub fn read(day: Day) -> Result<Output, AocCommandError> {
             puzzle_path,
         ],
         day,
+        config,
     );
 
     call_aoc_cli(&args)
 }
 
-pub fn download(day: Day) -> Result<Output, AocCommandError> {
-    let input_path = get_input_path(day);
-    let puzzle_path = get_puzzle_path(day);
+pub fn download(day: Day, config: &Config) -> Result<Output, AocCommandError> {
+    let input_path = config.input_path(day).display().to_string();
+    let puzzle_path = config.puzzle_path(day).display().to_string();
 
     let args = build_args(
         "download",
@@ -65,6 +66,7 @@ pub fn download(day: Day) -> Result<Output, AocCommandError> {
             puzzle_path.to_string(),
         ],
         day,
+        config,
     );
 
     let output = call_aoc_cli(&args)?;
@@ -74,33 +76,23 @@ pub fn download(day: Day) -> Result<Output, AocCommandError> {
     Ok(output)
 }
 
-pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandError> {
+pub fn submit(
+    day: Day,
+    part: u8,
+    result: &str,
+    config: &Config,
+) -> Result<Output, AocCommandError> {
     // workaround: the argument order is inverted for submit.
-    let mut args = build_args("submit", &[], day);
+    let mut args = build_args("submit", &[], day, config);
     args.push(part.to_string());
     args.push(result.to_string());
     call_aoc_cli(&args)
 }
 
-fn get_input_path(day: Day) -> String {
-    format!("data/inputs/{day}.txt")
-}
-
-fn get_puzzle_path(day: Day) -> String {
-    format!("data/puzzles/{day}.md")
-}
-
-fn get_year() -> Option<u16> {
-    match std::env::var("AOC_YEAR") {
-        Ok(x) => x.parse().ok().or(None),
-        Err(_) => None,
-    }
-}
-
-fn build_args(command: &str, args: &[String], day: Day) -> Vec<String> {
+fn build_args(command: &str, args: &[String], day: Day, config: &Config) -> Vec<String> {
     let mut cmd_args = args.to_vec();
 
-    if let Some(year) = get_year() {
+    if let Some(year) = config.year {
         cmd_args.push("--year".into());
         cmd_args.push(year.to_string());
     }
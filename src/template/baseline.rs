@@ -0,0 +1,259 @@
+/// Module that compares timings against a baseline to detect performance regressions.
+///
+/// Baselines are exported timings, either a file written by `--export` or a named run stored in [`BASELINE_DIR`].
+/// A part only counts as changed if the difference exceeds the threshold and the noise of both measurements.
+use std::path::{Path, PathBuf};
+
+use crate::template::{
+    export::{self, Format, Metadata},
+    readme_benchmarks::{Timing, Timings},
+    Day, ANSI_BOLD, ANSI_GREEN, ANSI_RED, ANSI_RESET,
+};
+
+/// Directory that named baselines are stored in.
+pub const BASELINE_DIR: &str = "target/benchmarks";
+
+/// Name of the baseline that every timed run is stored as.
+pub const LAST_RUN: &str = "last";
+
+/// Default relative change that counts as a regression.
+pub const DEFAULT_THRESHOLD: f64 = 0.1;
+
+/// How many standard errors the difference of two means needs to exceed to be considered significant.
+const SIGNIFICANCE: f64 = 2.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Improved,
+    Unchanged,
+    Regressed,
+    /// There is no baseline to compare with.
+    New,
+}
+
+impl Verdict {
+    fn label(self) -> String {
+        match self {
+            Verdict::Improved => format!("{ANSI_GREEN}improved{ANSI_RESET}"),
+            Verdict::Unchanged => "unchanged".into(),
+            Verdict::Regressed => format!("{ANSI_RED}{ANSI_BOLD}regressed{ANSI_RESET}"),
+            Verdict::New => "new".into(),
+        }
+    }
+}
+
+pub struct PartComparison {
+    pub part: &'static str,
+    pub baseline: Option<Timing>,
+    pub current: Timing,
+    pub verdict: Verdict,
+}
+
+pub struct DayComparison {
+    pub day: Day,
+    pub parts: Vec<PartComparison>,
+    pub verdict: Verdict,
+}
+
+/// Parses a threshold like `10%` or `10` into a fraction.
+pub fn parse_threshold(s: &str) -> Result<f64, String> {
+    let percent: f64 = s
+        .trim()
+        .trim_end_matches('%')
+        .parse()
+        .map_err(|_| format!("invalid threshold `{s}`, expecting a percentage like 10%"))?;
+
+    if percent < 0.0 {
+        return Err(format!("invalid threshold `{s}`, must not be negative"));
+    }
+
+    Ok(percent / 100.0)
+}
+
+/// Resolves a baseline argument: existing files are used as is, other values name a stored run.
+#[must_use]
+pub fn resolve(name_or_path: &str) -> PathBuf {
+    let path = PathBuf::from(name_or_path);
+    if path.is_file() {
+        path
+    } else {
+        Path::new(BASELINE_DIR).join(format!("{name_or_path}.json"))
+    }
+}
+
+/// Stores the timings as a named baseline.
+pub fn save(
+    name: &str,
+    timings: &[Timings],
+    metadata: &Metadata,
+) -> Result<PathBuf, export::Error> {
+    let path = Path::new(BASELINE_DIR).join(format!("{name}.json"));
+    export::export(&path, Format::Json, timings, metadata)?;
+    Ok(path)
+}
+
+/// Compares each measured part with its baseline. Days that are missing from `current` are skipped.
+#[must_use]
+pub fn compare(baseline: &[Timings], current: &[Timings], threshold: f64) -> Vec<DayComparison> {
+    current
+        .iter()
+        .map(|t| {
+            let base = baseline.iter().find(|b| b.day == t.day);
+
+            let parts: Vec<PartComparison> = [
+                ("Parse", &t.parse, base.and_then(|b| b.parse.as_ref())),
+                ("Part 1", &t.part_1, base.and_then(|b| b.part_1.as_ref())),
+                ("Part 2", &t.part_2, base.and_then(|b| b.part_2.as_ref())),
+            ]
+            .into_iter()
+            .filter_map(|(part, current, baseline)| {
+                let current = current.clone()?;
+                Some(PartComparison {
+                    part,
+                    verdict: baseline.map_or(Verdict::New, |b| verdict(b, &current, threshold)),
+                    baseline: baseline.cloned(),
+                    current,
+                })
+            })
+            .collect();
+
+            let has = |verdict| parts.iter().any(|p| p.verdict == verdict);
+            let verdict = if has(Verdict::Regressed) {
+                Verdict::Regressed
+            } else if has(Verdict::Improved) {
+                Verdict::Improved
+            } else if parts.iter().all(|p| p.verdict == Verdict::New) {
+                Verdict::New
+            } else {
+                Verdict::Unchanged
+            };
+
+            DayComparison {
+                day: t.day,
+                parts,
+                verdict,
+            }
+        })
+        .collect()
+}
+
+/// Judges the change from `baseline` to `current`.
+/// The change needs to exceed the threshold, and the difference of means needs to exceed the noise of both measurements.
+fn verdict(baseline: &Timing, current: &Timing, threshold: f64) -> Verdict {
+    if baseline.nanos <= 0.0 {
+        return Verdict::Unchanged;
+    }
+
+    let change = (current.nanos - baseline.nanos) / baseline.nanos;
+
+    #[allow(clippy::cast_precision_loss)]
+    let standard_error = |t: &Timing| {
+        let deviation = t.deviation_nanos.unwrap_or(0.0);
+        deviation * deviation / t.samples.max(1) as f64
+    };
+    let noise = (standard_error(baseline) + standard_error(current)).sqrt();
+    let is_significant = (current.nanos - baseline.nanos).abs() > SIGNIFICANCE * noise;
+
+    match change {
+        c if c > threshold && is_significant => Verdict::Regressed,
+        c if c < -threshold && is_significant => Verdict::Improved,
+        _ => Verdict::Unchanged,
+    }
+}
+
+/// Prints one line per day, listing the change of each part.
+pub fn print(comparisons: &[DayComparison], baseline: &str, threshold: f64) {
+    println!(
+        "\n{ANSI_BOLD}Comparison with baseline {baseline} (threshold {:.1}%):{ANSI_RESET}",
+        threshold * 100.0
+    );
+
+    for comparison in comparisons {
+        let parts: Vec<String> = comparison
+            .parts
+            .iter()
+            .map(|p| match &p.baseline {
+                Some(b) => format!(
+                    "{}: {} → {} ({:+.1}%)",
+                    p.part,
+                    b.formatted,
+                    p.current.formatted,
+                    (p.current.nanos - b.nanos) / b.nanos * 100.0
+                ),
+                None => format!("{}: {}", p.part, p.current.formatted),
+            })
+            .collect();
+
+        println!(
+            "Day {}: {}  {}",
+            comparison.day,
+            comparison.verdict.label(),
+            parts.join(", ")
+        );
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{compare, parse_threshold, verdict, Verdict};
+    use crate::{
+        day,
+        template::readme_benchmarks::{fixtures, Timing},
+    };
+
+    fn timing(nanos: f64, deviation: f64) -> Timing {
+        Timing {
+            deviation_nanos: Some(deviation),
+            ..fixtures::timing(&format!("{nanos}ns"), nanos)
+        }
+    }
+
+    #[test]
+    fn parses_thresholds() {
+        assert_eq!(parse_threshold("10%"), Ok(0.1));
+        assert_eq!(parse_threshold("2.5"), Ok(0.025));
+        assert!(parse_threshold("ten").is_err());
+        assert!(parse_threshold("-5%").is_err());
+    }
+
+    #[test]
+    fn judges_changes() {
+        let base = timing(1000.0, 10.0);
+        assert_eq!(
+            verdict(&base, &timing(1200.0, 10.0), 0.1),
+            Verdict::Regressed
+        );
+        assert_eq!(verdict(&base, &timing(800.0, 10.0), 0.1), Verdict::Improved);
+        assert_eq!(
+            verdict(&base, &timing(1050.0, 10.0), 0.1),
+            Verdict::Unchanged
+        );
+        // a large change within the noise of the measurements does not count.
+        assert_eq!(
+            verdict(&base, &timing(1200.0, 5000.0), 0.1),
+            Verdict::Unchanged
+        );
+    }
+
+    #[test]
+    fn compares_days() {
+        let timings = |day, part_1, part_2| fixtures::timings(day, Some(part_1), part_2);
+
+        let baseline = vec![timings(day!(1), timing(1000.0, 1.0), None)];
+        let current = vec![
+            timings(day!(1), timing(1000.0, 1.0), Some(timing(5000.0, 1.0))),
+            timings(day!(2), timing(10.0, 1.0), None),
+        ];
+
+        let comparisons = compare(&baseline, &current, 0.1);
+        assert_eq!(comparisons[0].verdict, Verdict::Unchanged);
+        assert_eq!(comparisons[0].parts[1].verdict, Verdict::New);
+        assert_eq!(comparisons[1].verdict, Verdict::New);
+
+        let current = vec![timings(day!(1), timing(2000.0, 1.0), None)];
+        assert_eq!(
+            compare(&baseline, &current, 0.1)[0].verdict,
+            Verdict::Regressed
+        );
+    }
+}
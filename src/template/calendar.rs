@@ -0,0 +1,188 @@
+/// Resolves relative day names like `today` against the advent calendar.
+///
+/// Puzzles unlock at midnight US Eastern time. As advent lies outside of daylight saving time,
+/// the calendar uses a fixed offset of UTC-5.
+use std::{
+    error::Error,
+    fmt::Display,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::template::Day;
+
+const EASTERN_OFFSET_SECS: i64 = -5 * 60 * 60;
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// A calendar date in US Eastern time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Returns today's date in US Eastern time.
+#[must_use]
+pub fn today() -> Date {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| i64::try_from(d.as_secs()).unwrap_or(i64::MAX));
+    date_from_unix_secs(secs)
+}
+
+/// Resolves `today` or `yesterday` to a [`Day`] of advent, given the current date and the configured year.
+/// Returns `Ok(None)` if `s` is not a relative day name.
+pub fn resolve(s: &str, today: Date, year: Option<u16>) -> Result<Option<Day>, RelativeDayError> {
+    let date = match s {
+        "today" => today,
+        "yesterday" => today.previous(),
+        _ => return Ok(None),
+    };
+
+    if date.month != 12 || date.day > 25 {
+        return Err(RelativeDayError::OutsideOfAdvent {
+            name: s.to_string(),
+            date,
+        });
+    }
+
+    if let Some(year) = year {
+        if i64::from(year) != date.year {
+            return Err(RelativeDayError::WrongYear {
+                name: s.to_string(),
+                date,
+                year,
+            });
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    let day = Day::new(date.day as u8);
+    Ok(day)
+}
+
+impl Date {
+    fn previous(self) -> Self {
+        date_from_days(days_from_date(self) - 1)
+    }
+}
+
+fn date_from_unix_secs(secs: i64) -> Date {
+    date_from_days((secs + EASTERN_OFFSET_SECS).div_euclid(SECS_PER_DAY))
+}
+
+/// Converts days since the unix epoch to a date.
+/// see: https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn date_from_days(days: i64) -> Date {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    Date { year, month, day }
+}
+
+/// Converts a date to days since the unix epoch.
+/// see: https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_date(date: Date) -> i64 {
+    let year = date.year - i64::from(date.month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = i64::from(date.month);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + i64::from(date.day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146_097 + doe - 719_468
+}
+
+/// An error which can be returned when resolving a relative day name.
+#[derive(Debug)]
+pub enum RelativeDayError {
+    OutsideOfAdvent { name: String, date: Date },
+    WrongYear { name: String, date: Date, year: u16 },
+}
+
+impl Error for RelativeDayError {}
+
+impl Display for RelativeDayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RelativeDayError::OutsideOfAdvent { name, date } => write!(
+                f,
+                "`{name}` is {date} in US Eastern time, which is not a day of advent (December 1st to 25th)"
+            ),
+            RelativeDayError::WrongYear { name, date, year } => write!(
+                f,
+                "`{name}` is {date} in US Eastern time, but the configured year is {year}"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        date_from_days, date_from_unix_secs, days_from_date, resolve, Date, RelativeDayError,
+    };
+    use crate::day;
+
+    fn date(year: i64, month: u32, day: u32) -> Date {
+        Date { year, month, day }
+    }
+
+    #[test]
+    fn converts_days_to_dates() {
+        assert_eq!(date_from_days(0), date(1970, 1, 1));
+        assert_eq!(date_from_days(19_692), date(2023, 12, 1));
+        assert_eq!(date_from_days(-1), date(1969, 12, 31));
+        assert_eq!(days_from_date(date(2024, 3, 1)), 19_783);
+        assert_eq!(date_from_days(19_783 - 1), date(2024, 2, 29));
+    }
+
+    #[test]
+    fn uses_eastern_time() {
+        // 2023-12-01T04:59:59Z is still November 30th in US Eastern time.
+        assert_eq!(date_from_unix_secs(1_701_406_799), date(2023, 11, 30));
+        assert_eq!(date_from_unix_secs(1_701_406_800), date(2023, 12, 1));
+    }
+
+    #[test]
+    fn resolves_relative_days() {
+        let today = date(2023, 12, 8);
+        assert_eq!(resolve("today", today, Some(2023)).unwrap(), Some(day!(8)));
+        assert_eq!(resolve("yesterday", today, None).unwrap(), Some(day!(7)));
+        assert_eq!(resolve("8", today, None).unwrap(), None);
+        assert_eq!(
+            resolve("yesterday", date(2023, 12, 26), None).unwrap(),
+            Some(day!(25))
+        );
+    }
+
+    #[test]
+    fn rejects_days_outside_of_advent() {
+        assert!(resolve("today", date(2023, 12, 26), None).is_err());
+        assert!(resolve("yesterday", date(2023, 12, 1), None).is_err());
+        assert!(resolve("today", date(2023, 10, 19), None).is_err());
+        assert!(resolve("today", date(2023, 12, 8), Some(2022)).is_err());
+    }
+
+    #[test]
+    fn reports_days_outside_of_advent_before_the_year() {
+        let error = resolve("today", date(2026, 10, 19), Some(2023)).unwrap_err();
+        assert!(matches!(error, RelativeDayError::OutsideOfAdvent { .. }));
+        assert!(error.to_string().contains("not a day of advent"));
+    }
+}
@@ -0,0 +1,513 @@
+/// Declarative model of the command-line interface.
+/// It drives `--help` output, validation of flags and the generated shell completions.
+use std::{error::Error, fmt::Write};
+
+use crate::template::{calendar, config, Day, ANSI_BOLD, ANSI_RESET};
+
+pub struct Command {
+    pub name: &'static str,
+    pub about: &'static str,
+    pub positional: Option<Positional>,
+    pub flags: &'static [Flag],
+}
+
+pub struct Positional {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub values: Values,
+}
+
+pub struct Flag {
+    pub name: &'static str,
+    pub help: &'static str,
+    /// Name and possible values of the flag's argument, for flags that take one.
+    pub value: Option<(&'static str, Values)>,
+}
+
+/// Values that are offered when completing an argument.
+#[derive(Clone, Copy)]
+pub enum Values {
+    /// Days that have a solution in `src/bin`.
+    SolvedDays,
+    /// Days that do not have a solution in `src/bin` yet.
+    UnsolvedDays,
+    /// Any file path.
+    Path,
+    /// Names of all commands.
+    Commands,
+    OneOf(&'static [&'static str]),
+    /// Free-form values, nothing is offered.
+    Any,
+}
+
+const DAY: Positional = Positional {
+    name: "day",
+    help: "Day number between 1 and 25, today or yesterday",
+    values: Values::SolvedDays,
+};
+
+const RELEASE: Flag = Flag {
+    name: "--release",
+    help: "Run an optimized build",
+    value: None,
+};
+
+const TIME: Flag = Flag {
+    name: "--time",
+    help: "Benchmark each part and print the average execution time",
+    value: None,
+};
+
+const BENCH_TIME: Flag = Flag {
+    name: "--bench-time",
+    help: "Approximate time spent benchmarking each part, e.g. 500ms",
+    value: Some(("duration", Values::Any)),
+};
+
+const MIN_SAMPLES: Flag = Flag {
+    name: "--min-samples",
+    help: "Minimum number of samples taken of each part",
+    value: Some(("count", Values::Any)),
+};
+
+const MAX_SAMPLES: Flag = Flag {
+    name: "--max-samples",
+    help: "Maximum number of samples taken of each part",
+    value: Some(("count", Values::Any)),
+};
+
+const WARMUP: Flag = Flag {
+    name: "--warmup",
+    help: "Time spent running each part before benchmarking it, e.g. 100ms",
+    value: Some(("duration", Values::Any)),
+};
+
+const THREADS: Flag = Flag {
+    name: "--threads",
+    help: "Size of the rayon thread pool, defaults to one thread per core",
+    value: Some(("count", Values::Any)),
+};
+
+pub const COMMANDS: &[Command] = &[
+    Command {
+        name: "scaffold",
+        about: "Create the solution, input and example files for a day",
+        positional: Some(Positional {
+            values: Values::UnsolvedDays,
+            ..DAY
+        }),
+        flags: &[Flag {
+            name: "--download",
+            help: "Download the input and puzzle description after scaffolding",
+            value: None,
+        }],
+    },
+    Command {
+        name: "download",
+        about: "Download the input and puzzle description for a day via aoc-cli",
+        positional: Some(DAY),
+        flags: &[],
+    },
+    Command {
+        name: "read",
+        about: "Print the puzzle description for a day via aoc-cli",
+        positional: Some(DAY),
+        flags: &[],
+    },
+    Command {
+        name: "solve",
+        about: "Run the solution for a day",
+        positional: Some(DAY),
+        flags: &[
+            RELEASE,
+            TIME,
+            BENCH_TIME,
+            MIN_SAMPLES,
+            MAX_SAMPLES,
+            WARMUP,
+            THREADS,
+            Flag {
+                name: "--scaling",
+                help: "Benchmark with 1, 2, 4 up to all cores or --threads and report the speedup",
+                value: None,
+            },
+            Flag {
+                name: "--dhat",
+                help: "Profile heap allocations with DHAT",
+                value: None,
+            },
+            Flag {
+                name: "--profile-cpu",
+                help: "Sample each part for the bench time and write flamegraphs to target/profiles",
+                value: None,
+            },
+            Flag {
+                name: "--visualize",
+                help: "Animate the frames that the solution draws in the terminal",
+                value: None,
+            },
+            Flag {
+                name: "--frames",
+                help: "Write the frames that the solution draws as images to the given directory",
+                value: Some(("dir", Values::Path)),
+            },
+            Flag {
+                name: "--frame-format",
+                help: "Image format of the frames written with --frames, defaults to png",
+                value: Some(("format", Values::OneOf(&["png", "ppm"]))),
+            },
+            Flag {
+                name: "--verbose",
+                help: "Print the debug messages of the solution, -vv prints trace messages as well",
+                value: None,
+            },
+            Flag {
+                name: "--submit",
+                help: "Submit the answer of the given part via aoc-cli",
+                value: Some(("part", Values::OneOf(&["1", "2"]))),
+            },
+            Flag {
+                name: "--watch",
+                help: "Re-run the solution whenever its source or data files change",
+                value: None,
+            },
+            Flag {
+                name: "--test",
+                help: "Run the unit tests of the day instead of the solution",
+                value: None,
+            },
+            Flag {
+                name: "--input",
+                help: "Read the input from the given file instead, - reads from stdin",
+                value: Some(("path", Values::Path)),
+            },
+            Flag {
+                name: "--variant",
+                help: "Run the variant with the given name instead of the default solution of each part that has one",
+                value: Some(("name", Values::Any)),
+            },
+            Flag {
+                name: "--compare",
+                help: "Benchmark all variants of each part, check that they agree and compare their speed",
+                value: None,
+            },
+            Flag {
+                name: "--generated",
+                help: "Benchmark on generated inputs of scale 1, 2, 4 up to the given one and report the growth",
+                value: Some(("scale", Values::Any)),
+            },
+        ],
+    },
+    Command {
+        name: "gen",
+        about: "Write a generated input for a day to data/generated",
+        positional: Some(DAY),
+        flags: &[Flag {
+            name: "--scale",
+            help: "Size of the generated input, defaults to 1",
+            value: Some(("scale", Values::Any)),
+        }],
+    },
+    Command {
+        name: "all",
+        about: "Run the solutions for all days",
+        positional: None,
+        flags: &[
+            RELEASE,
+            TIME,
+            BENCH_TIME,
+            MIN_SAMPLES,
+            MAX_SAMPLES,
+            WARMUP,
+            THREADS,
+            Flag {
+                name: "--dhat",
+                help: "Profile the heap usage of each day with DHAT in a separate run",
+                value: None,
+            },
+            Flag {
+                name: "--report",
+                help: "Write an HTML benchmark report with charts to the given directory",
+                value: Some(("dir", Values::Path)),
+            },
+            Flag {
+                name: "--export",
+                help: "Export the timings as csv or json, requires --export-path",
+                value: Some(("format", Values::OneOf(&["csv", "json"]))),
+            },
+            Flag {
+                name: "--export-path",
+                help: "File to export the timings to",
+                value: Some(("path", Values::Path)),
+            },
+            Flag {
+                name: "--import",
+                help: "Update the readme from exported timings instead of running the solutions",
+                value: Some(("path", Values::Path)),
+            },
+            Flag {
+                name: "--baseline",
+                help: "Compare the timings with an exported file or a saved run and fail on regressions",
+                value: Some(("name", Values::Path)),
+            },
+            Flag {
+                name: "--threshold",
+                help: "Relative slowdown that counts as a regression, defaults to 10%",
+                value: Some(("percent", Values::Any)),
+            },
+            Flag {
+                name: "--save-baseline",
+                help: "Save the timings as a named run to compare with later",
+                value: Some(("name", Values::Any)),
+            },
+        ],
+    },
+    Command {
+        name: "completions",
+        about: "Print a shell completion script",
+        positional: Some(Positional {
+            name: "shell",
+            help: "One of bash, zsh or fish",
+            values: Values::OneOf(&["bash", "zsh", "fish"]),
+        }),
+        flags: &[],
+    },
+    Command {
+        name: "help",
+        about: "Print help for a command",
+        positional: Some(Positional {
+            name: "command",
+            help: "The command to print help for",
+            values: Values::Commands,
+        }),
+        flags: &[],
+    },
+];
+
+#[must_use]
+pub fn find(name: &str) -> Option<&'static Command> {
+    COMMANDS.iter().find(|c| c.name == name)
+}
+
+/// Formats the overview of all commands.
+#[must_use]
+pub fn help() -> String {
+    let mut s = String::new();
+
+    let _ = writeln!(s, "{ANSI_BOLD}Usage:{ANSI_RESET} cargo <command> [options]");
+    let _ = writeln!(s);
+    let _ = writeln!(s, "{ANSI_BOLD}Commands:{ANSI_RESET}");
+
+    let width = COMMANDS.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    for command in COMMANDS {
+        let _ = writeln!(s, "  {:width$}  {}", command.name, command.about);
+    }
+
+    let _ = writeln!(s);
+    let _ = write!(
+        s,
+        "Run `cargo <command> --help` for more information on a command."
+    );
+
+    s
+}
+
+impl Command {
+    /// Formats the help text of the command, listing its arguments and flags.
+    #[must_use]
+    pub fn help(&self) -> String {
+        let mut s = String::new();
+
+        let _ = writeln!(s, "{}", self.about);
+        let _ = writeln!(s);
+
+        let mut usage = format!("cargo {}", self.name);
+        if let Some(positional) = &self.positional {
+            let _ = write!(usage, " <{}>", positional.name);
+        }
+        usage.push_str(" [options]");
+        let _ = writeln!(s, "{ANSI_BOLD}Usage:{ANSI_RESET} {usage}");
+
+        if let Some(positional) = &self.positional {
+            let _ = writeln!(s);
+            let _ = writeln!(s, "{ANSI_BOLD}Arguments:{ANSI_RESET}");
+            let _ = writeln!(s, "  <{}>  {}", positional.name, positional.help);
+        }
+
+        let flags: Vec<(String, &str)> = self
+            .flags
+            .iter()
+            .map(|flag| match &flag.value {
+                Some((value, _)) => (format!("{} <{value}>", flag.name), flag.help),
+                None => (flag.name.to_string(), flag.help),
+            })
+            .chain([("-h, --help".to_string(), "Print help")])
+            .collect();
+
+        let width = flags.iter().map(|(f, _)| f.len()).max().unwrap_or(0);
+
+        let _ = writeln!(s);
+        let _ = writeln!(s, "{ANSI_BOLD}Options:{ANSI_RESET}");
+        for (flag, help) in flags {
+            let _ = writeln!(s, "  {flag:width$}  {help}");
+        }
+
+        s.trim_end().to_string()
+    }
+
+    #[must_use]
+    pub fn flag(&self, name: &str) -> Option<&'static Flag> {
+        self.flags.iter().find(|f| f.name == name)
+    }
+
+    /// Checks that all flags in `args` are known to the command, suggesting similar flags for typos.
+    /// Values can be passed as the next argument or inline, as in `--flag=value`.
+    pub fn validate(&self, args: &[String]) -> Result<(), String> {
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            if !arg.starts_with("--") {
+                continue;
+            }
+
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (arg.as_str(), None),
+            };
+
+            match (self.flag(name), inline_value) {
+                (Some(Flag { value: Some(_), .. }), None) => {
+                    // skip the flag's value, it might start with dashes.
+                    args.next();
+                }
+                (Some(Flag { value: None, .. }), Some(_)) => {
+                    return Err(format!("option `{name}` does not take a value"));
+                }
+                (Some(_), _) => {}
+                (None, _) => {
+                    let mut message =
+                        format!("unknown option `{name}` for command `{}`", self.name);
+                    if let Some(suggestion) = suggest(name, self.flags.iter().map(|f| f.name)) {
+                        let _ = write!(message, "\n\n  did you mean `{suggestion}`?");
+                    }
+                    return Err(message);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a day argument. Besides day numbers, this accepts `today` and `yesterday`,
+/// which are resolved against the advent calendar and the configured year.
+pub fn parse_day(s: &str) -> Result<Day, Box<dyn Error>> {
+    match calendar::resolve(s, calendar::today(), config::get().year)? {
+        Some(day) => Ok(day),
+        None => Ok(s.parse()?),
+    }
+}
+
+/// Returns the candidate that is closest to `input`, if it is close enough to be a likely typo.
+pub fn suggest<'a>(input: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    candidates
+        .map(|candidate| (levenshtein(input, candidate), candidate))
+        .filter(|(distance, candidate)| *distance <= candidate.len().div_ceil(3))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous + usize::from(ca != *cb);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{find, levenshtein, suggest, COMMANDS};
+
+    #[test]
+    fn computes_levenshtein_distance() {
+        assert_eq!(levenshtein("solve", "solve"), 0);
+        assert_eq!(levenshtein("slove", "solve"), 2);
+        assert_eq!(levenshtein("--relase", "--release"), 1);
+        assert_eq!(levenshtein("", "all"), 3);
+    }
+
+    #[test]
+    fn suggests_similar_commands() {
+        let names = || COMMANDS.iter().map(|c| c.name);
+        assert_eq!(suggest("slove", names()), Some("solve"));
+        assert_eq!(suggest("scafold", names()), Some("scaffold"));
+        assert_eq!(suggest("foo", names()), None);
+    }
+
+    #[test]
+    fn rejects_unknown_flags() {
+        let solve = find("solve").unwrap();
+
+        assert!(solve
+            .validate(&["1".into(), "--release".into(), "--time".into()])
+            .is_ok());
+        assert!(solve
+            .validate(&["1".into(), "--input".into(), "--weird.txt".into()])
+            .is_ok());
+
+        let err = solve
+            .validate(&["1".into(), "--relase".into()])
+            .unwrap_err();
+        assert!(err.contains("did you mean `--release`?"));
+    }
+
+    #[test]
+    fn accepts_inline_values() {
+        let solve = find("solve").unwrap();
+
+        assert!(solve
+            .validate(&[
+                "--submit=1".into(),
+                "--input=--weird.txt".into(),
+                "1".into()
+            ])
+            .is_ok());
+        assert!(solve
+            .validate(&["--input=in.txt".into(), "--release".into()])
+            .is_ok());
+
+        let err = solve.validate(&["--relase=1".into()]).unwrap_err();
+        assert!(err.contains("unknown option `--relase`"));
+        assert!(err.contains("did you mean `--release`?"));
+
+        assert!(solve.validate(&["--release=yes".into()]).is_err());
+    }
+
+    #[test]
+    fn help_texts_are_safe_for_completion_scripts() {
+        let texts = COMMANDS.iter().flat_map(|c| {
+            [c.about]
+                .into_iter()
+                .chain(c.flags.iter().map(|f| f.help))
+                .chain(c.positional.iter().map(|p| p.help))
+        });
+
+        for text in texts {
+            assert!(
+                !text.contains(['\'', '"', '[', ']', ':', '$', '`']),
+                "{text}"
+            );
+        }
+    }
+}
@@ -1,13 +1,70 @@
-use std::io;
+use std::{io, path::PathBuf, process};
 
 use crate::template::{
-    all_days,
-    readme_benchmarks::{self, Timings},
-    Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
+    all_days, baseline,
+    config::{BenchSettings, Config},
+    export::{self, Format, Metadata},
+    readme_benchmarks::{self, SourceHashes, Timings},
+    report, ANSI_BOLD, ANSI_ITALIC, ANSI_RED, ANSI_RESET,
 };
 
-pub fn handle(is_release: bool, is_timed: bool) {
+/// Options of the `all` command.
+#[derive(Default)]
+pub struct Options {
+    pub release: bool,
+    pub time: bool,
+    /// Benchmark settings that are passed to the solutions with `--time`.
+    pub bench: BenchSettings,
+    /// Size of the rayon thread pool the solutions run with.
+    pub threads: Option<usize>,
+    /// Profile the heap usage of each day with dhat.
+    pub dhat: bool,
+    /// Directory to write the HTML benchmark report to.
+    pub report: Option<PathBuf>,
+    /// Format and path to export the timings to.
+    pub export: Option<(Format, PathBuf)>,
+    /// Path of previously exported timings. The solutions are not run if this is set.
+    pub import: Option<PathBuf>,
+    /// Exported file or name of a saved run to compare the timings with.
+    pub baseline: Option<String>,
+    /// Relative slowdown that counts as a regression.
+    pub threshold: f64,
+    /// Name to save the timings as, in addition to the last run.
+    pub save_baseline: Option<String>,
+}
+
+pub fn handle(options: &Options, config: &Config) {
+    if let Err(message) = validate(options) {
+        eprintln!("{message}");
+        process::exit(1);
+    }
+
+    if let Some(path) = &options.import {
+        let (timings, metadata) = match export::import(path) {
+            Ok(imported) => imported,
+            Err(e) => {
+                eprintln!("Failed to import timings from {}: {e:?}", path.display());
+                process::exit(1);
+            }
+        };
+
+        println!(
+            "Imported timings of {} days from {} ({}).",
+            timings.len(),
+            path.display(),
+            describe(&metadata)
+        );
+
+        publish(timings, options, true, &metadata.source_hashes, config);
+        return;
+    }
+
     let mut timings: Vec<Timings> = vec![];
+    let timed = options.time.then_some(&options.bench);
+
+    if let Some(bench) = timed {
+        println!("{ANSI_ITALIC}Benchmarking with {bench}.{ANSI_RESET}\n");
+    }
 
     all_days().for_each(|day| {
         if day > 1 {
@@ -17,32 +74,221 @@ pub fn handle(is_release: bool, is_timed: bool) {
         println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
         println!("------");
 
-        let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+        let output = child_commands::run_solution(
+            day,
+            timed,
+            options.threads,
+            options.release,
+            false,
+            &[],
+            config,
+        )
+        .unwrap();
 
         if output.is_empty() {
             println!("Not solved.");
         } else {
-            let val = child_commands::parse_exec_time(&output, day);
+            let mut val = child_commands::parse_exec_time(&output, day);
+
+            if options.dhat {
+                // profile in a separate run, the dhat allocator would distort the timings.
+                println!("{ANSI_ITALIC}Profiling heap usage...{ANSI_RESET}");
+                let output = child_commands::run_solution(
+                    day,
+                    None,
+                    options.threads,
+                    false,
+                    true,
+                    &[],
+                    config,
+                )
+                .unwrap();
+                val.heap = child_commands::parse_heap(&output);
+            }
+
             timings.push(val);
         }
     });
 
-    if is_timed {
-        let total_millis = timings.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64;
+    if options.dhat {
+        print_heap_summary(&timings);
+    }
+
+    let has_failures = print_failures(&timings);
 
-        println!("\n{ANSI_BOLD}Total:{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}");
+    if options.time {
+        let metadata = Metadata::collect(options.release, Some(options.bench), config);
 
-        if is_release {
-            match readme_benchmarks::update(timings, total_millis) {
-                Ok(()) => println!("Successfully updated README with benchmarks."),
-                Err(_) => {
-                    eprintln!("Failed to update readme with benchmarks.");
+        if let Some((format, path)) = &options.export {
+            match export::export(path, *format, &timings, &metadata) {
+                Ok(()) => println!("Exported timings to {}.", path.display()),
+                Err(e) => {
+                    eprintln!("Failed to export timings to {}: {e:?}", path.display());
                 }
             }
         }
+
+        let has_regressions = options
+            .baseline
+            .as_ref()
+            .is_some_and(|name| compare_with_baseline(name, &timings, options.threshold));
+
+        let names = [Some(baseline::LAST_RUN), options.save_baseline.as_deref()];
+        for name in names.into_iter().flatten() {
+            if let Err(e) = baseline::save(name, &timings, &metadata) {
+                eprintln!("Failed to save timings as baseline {name}: {e:?}");
+            }
+        }
+
+        publish(
+            timings,
+            options,
+            options.release,
+            &metadata.source_hashes,
+            config,
+        );
+
+        if has_regressions {
+            eprintln!("\nTimings regressed compared to the baseline.");
+            process::exit(1);
+        }
+    }
+
+    if has_failures {
+        process::exit(1);
     }
 }
 
+/// Rejects options that would be ignored because they only apply to benchmarked timings.
+fn validate(options: &Options) -> Result<(), String> {
+    if options.report.is_some() && !options.time && options.import.is_none() {
+        return Err("`--report` requires `--time` or `--import`.".into());
+    }
+
+    let timed_only = [
+        ("--export", options.export.is_some()),
+        ("--baseline", options.baseline.is_some()),
+        ("--save-baseline", options.save_baseline.is_some()),
+    ];
+    if let Some((flag, _)) = timed_only.iter().find(|(_, set)| *set && !options.time) {
+        return Err(format!("`{flag}` requires `--time`."));
+    }
+
+    Ok(())
+}
+
+/// Lists the steps that returned an error or panicked. Returns whether any step failed.
+fn print_failures(timings: &[Timings]) -> bool {
+    let failures: Vec<_> = timings
+        .iter()
+        .flat_map(|t| t.failures.iter().map(move |f| (t.day, f)))
+        .collect();
+
+    if failures.is_empty() {
+        return false;
+    }
+
+    eprintln!("\n{ANSI_RED}{ANSI_BOLD}Failed parts:{ANSI_RESET}");
+    for (day, failure) in failures {
+        let kind = if failure.panicked { " panicked" } else { "" };
+        eprintln!(
+            "{ANSI_RED}Day {day} {}{kind}: {}{ANSI_RESET}",
+            failure.step, failure.message
+        );
+    }
+
+    true
+}
+
+/// Compares the timings with a baseline and prints the result. Returns whether any day regressed.
+fn compare_with_baseline(name: &str, timings: &[Timings], threshold: f64) -> bool {
+    let path = baseline::resolve(name);
+
+    let baseline_timings = match export::import(&path) {
+        Ok((baseline_timings, _)) => baseline_timings,
+        Err(e) => {
+            eprintln!("Failed to read baseline from {}: {e:?}", path.display());
+            process::exit(1);
+        }
+    };
+
+    let comparisons = baseline::compare(&baseline_timings, timings, threshold);
+    baseline::print(&comparisons, name, threshold);
+
+    comparisons
+        .iter()
+        .any(|c| c.verdict == baseline::Verdict::Regressed)
+}
+
+fn print_heap_summary(timings: &[Timings]) {
+    println!("\n{ANSI_BOLD}Heap usage:{ANSI_RESET}");
+
+    for t in timings {
+        match t.heap {
+            Some(heap) => println!(
+                "Day {}: {} allocated in {} blocks, {} at peak",
+                t.day,
+                readme_benchmarks::format_bytes(heap.total_bytes),
+                heap.total_blocks,
+                readme_benchmarks::format_bytes(heap.peak_bytes)
+            ),
+            None => println!("Day {}: -", t.day),
+        }
+    }
+}
+
+/// Prints the total and writes the timings to the report and readme.
+/// `source_hashes` are the hashes of the sources the timings were measured on.
+fn publish(
+    timings: Vec<Timings>,
+    options: &Options,
+    update_readme: bool,
+    source_hashes: &SourceHashes,
+    config: &Config,
+) {
+    let total_millis = timings.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64;
+
+    println!("\n{ANSI_BOLD}Total:{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}");
+
+    if let Some(dir) = &options.report {
+        match report::write(dir, &timings, source_hashes, config) {
+            Ok(()) => println!(
+                "Wrote benchmark report to {}.",
+                dir.join("index.html").display()
+            ),
+            Err(e) => {
+                eprintln!(
+                    "Failed to write benchmark report to {}: {e:?}",
+                    dir.display()
+                );
+            }
+        }
+    }
+
+    if update_readme {
+        match readme_benchmarks::update(timings, total_millis, source_hashes, config) {
+            Ok(()) => println!("Successfully updated README with benchmarks."),
+            Err(_) => {
+                eprintln!("Failed to update readme with benchmarks.");
+            }
+        }
+    }
+}
+
+fn describe(metadata: &Metadata) -> String {
+    let mut parts = vec![format!("{} profile", metadata.profile)];
+    if let Some(bench) = &metadata.bench {
+        parts.push(bench.to_string());
+    }
+    if let Some(commit) = &metadata.commit {
+        parts.push(format!("commit {}", &commit[..commit.len().min(7)]));
+    }
+    if let Some(rustc) = &metadata.rustc {
+        parts.push(rustc.clone());
+    }
+    parts.join(", ")
+}
+
 #[derive(Debug)]
 pub enum Error {
     BrokenPipe,
@@ -56,48 +302,71 @@ impl From<std::io::Error> for Error {
     }
 }
 
-#[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
-}
-
 /// All solutions live in isolated binaries.
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
-mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+pub(super) mod child_commands {
+    use super::Error;
+    use crate::template::{
+        config::{BenchSettings, Config},
+        readme_benchmarks::{self, Failure, HeapUsage, Timing},
+        runner::RAW_TIMINGS_VAR,
+        Day, ANSI_RESET,
+    };
     use std::{
         io::{BufRead, BufReader},
-        path::Path,
         process::{Command, Stdio},
         thread,
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    /// Run the solution bin for a given day. With `is_dhat`, the solution is built with the dhat allocator.
+    /// `extra_args` are passed to the solution as they are.
+    pub fn run_solution(
+        day: Day,
+        bench: Option<&BenchSettings>,
+        threads: Option<usize>,
+        is_release: bool,
+        is_dhat: bool,
+        extra_args: &[String],
+        config: &Config,
+    ) -> Result<Vec<String>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
-        if !Path::new(&get_path_for_bin(day)).exists() {
+        if !config.bin_path(day).exists() {
             return Ok(vec![]);
         }
 
-        let day_padded = day.to_string();
-        let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+        let mut args = vec![
+            "run".to_string(),
+            "--quiet".into(),
+            "--bin".into(),
+            day.to_string(),
+        ];
+
+        if is_dhat {
+            args.extend(["--profile", "dhat", "--features", "dhat-heap"].map(String::from));
+        } else if is_release {
+            args.push("--release".into());
+        }
+
+        args.push("--".into());
 
-        if is_release {
-            args.push("--release");
+        if let Some(bench) = bench {
+            // mirror `--time` flag and benchmark settings to child invocations.
+            args.push("--time".into());
+            args.extend(bench.to_args());
         }
 
-        if is_timed {
-            // mirror `--time` flag to child invocations.
-            args.push("--");
-            args.push("--time");
+        if let Some(threads) = threads {
+            args.extend(["--threads".into(), threads.to_string()]);
         }
 
+        args.extend_from_slice(extra_args);
+
         // spawn child command with piped stdout/stderr.
         // forward output to stdout/stderr while grabbing stdout lines.
 
         let mut cmd = Command::new("cargo")
             .args(&args)
+            .env(RAW_TIMINGS_VAR, "1")
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()?;
@@ -115,7 +384,11 @@ mod child_commands {
 
         for line in stdout.lines() {
             let line = line.unwrap();
-            println!("{line}");
+            // the exact durations are only meant for parsing, the formatted ones are shown instead.
+            // heap profiles only show the heap usage, the solution's output was shown by the timed run.
+            if parse_raw_timing(&line).is_none() && (!is_dhat || parse_heap_line(&line).is_some()) {
+                println!("{line}");
+            }
             output.push(line);
         }
 
@@ -128,9 +401,12 @@ mod child_commands {
     pub fn parse_exec_time(output: &[String], day: Day) -> super::Timings {
         let mut timings = super::Timings {
             day,
+            parse: None,
             part_1: None,
             part_2: None,
             total_nanos: 0_f64,
+            heap: None,
+            failures: parse_failures(output),
         };
 
         output
@@ -140,50 +416,128 @@ mod child_commands {
                     return None;
                 }
 
-                let Some((timing_str, nanos)) = parse_time(l) else {
+                let Some(timing) = parse_time(l) else {
                     eprintln!("Could not parse timings from line: {l}");
                     return None;
                 };
 
                 let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
+                Some((part, timing))
             })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
+            .for_each(|(part, timing)| {
+                if part.contains("Parse") {
+                    timings.parse = Some(timing);
+                } else if part.contains("Part 1") {
+                    timings.part_1 = Some(timing);
                 } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
+                    timings.part_2 = Some(timing);
                 }
-
-                timings.total_nanos += nanos;
             });
 
+        // prefer the exact durations over the ones parsed from the rounded output.
+        for (label, [nanos, deviation, cold]) in output.iter().filter_map(|l| parse_raw_timing(l)) {
+            let timing = match label {
+                "Parse" => &mut timings.parse,
+                "Part 1" => &mut timings.part_1,
+                "Part 2" => &mut timings.part_2,
+                _ => continue,
+            };
+
+            if let Some(timing) = timing {
+                timing.nanos = nanos;
+                timing.deviation_nanos = timing.deviation_nanos.map(|_| deviation);
+                timing.cold_nanos = timing.cold_nanos.map(|_| cold);
+            }
+        }
+
+        timings.total_nanos = [&timings.parse, &timings.part_1, &timings.part_2]
+            .into_iter()
+            .flatten()
+            .map(|t| t.nanos)
+            .sum();
+
         timings
     }
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
+    /// Parses the exact durations that the runner prints for the parse step and each part,
+    /// e.g. `Part 1 nanos: 74130 1200 1500000` for the mean, deviation and cold run.
+    fn parse_raw_timing(line: &str) -> Option<(&str, [f64; 3])> {
+        let (label, nanos) = line.split_once(" nanos: ")?;
+        if !matches!(label, "Parse" | "Part 1" | "Part 2") {
+            return None;
+        }
+
+        let mut nanos = nanos.split(' ').map(str::parse);
+        let mut next = || nanos.next()?.ok();
+        Some((label, [next()?, next()?, next()?]))
+    }
+
+    /// Parses the steps that the runner reported as failed or panicked,
+    /// e.g. `Part 1: ✖ failed: invalid card` or `Parse: ✖ panicked: oops at src/bin/05.rs:3:5`.
+    pub fn parse_failures(output: &[String]) -> Vec<Failure> {
+        output
+            .iter()
+            .filter_map(|l| {
+                let step = l.split(':').next()?.parse().ok()?;
+                let (panicked, message) = match l.split_once("✖ failed: ") {
+                    Some((_, message)) => (false, message),
+                    None => (true, l.split_once("✖ panicked: ")?.1),
+                };
+                Some(Failure {
+                    step,
+                    message: message.trim_end_matches(ANSI_RESET.code()).to_string(),
+                    panicked,
+                })
+            })
+            .collect()
+    }
+
+    /// Parses the heap usage that the runner prints for each part and the parse step, combining them.
+    pub fn parse_heap(output: &[String]) -> Option<HeapUsage> {
+        output
+            .iter()
+            .filter_map(|l| parse_heap_line(l))
+            .reduce(HeapUsage::merge)
+    }
+
+    /// Parses the heap usage of a step, e.g. `Part 1 heap: 144 bytes in 12 blocks, 24 bytes at peak`.
+    fn parse_heap_line(line: &str) -> Option<HeapUsage> {
+        let (step, usage) = line.split_once(" heap: ")?;
+        if !step.starts_with("Part ") && step != "Parse" {
+            return None;
+        }
+        usage.parse().ok()
     }
 
-    fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
-        let str_timing = line
+    fn parse_time(line: &str) -> Option<Timing> {
+        let (str_timing, str_samples) = line
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
-            .split('@')
-            .next()?
-            .trim();
+            .next_back()?
+            .split_once('@')?;
+
+        // benchmarked parts are prefixed with the duration of the cold run, e.g. `cold 1.2ms, warm 74.13ns`.
+        let (str_cold, str_timing) = match str_timing.split_once(", warm ") {
+            Some((cold, timing)) => (cold.trim().strip_prefix("cold "), timing),
+            None => (None, str_timing),
+        };
 
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
+        let (str_timing, str_deviation) = match str_timing.split_once('±') {
+            Some((timing, deviation)) => (timing.trim(), Some(deviation.trim())),
+            None => (str_timing.trim(), None),
+        };
+        let samples = str_samples.trim().parse().ok()?;
 
-        Some((str_timing, parsed_timing))
+        let parsed_timing = readme_benchmarks::parse_nanos(str_timing)?;
+
+        Some(Timing {
+            formatted: str_timing.into(),
+            nanos: parsed_timing,
+            samples,
+            deviation_nanos: str_deviation.and_then(readme_benchmarks::parse_nanos),
+            cold_nanos: str_cold.and_then(readme_benchmarks::parse_nanos),
+        })
     }
 
     /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
@@ -202,9 +556,70 @@ mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{parse_exec_time, parse_failures, parse_heap};
+
+        use crate::{day, template::readme_benchmarks::Step};
+
+        #[test]
+        fn test_deviation() {
+            let res = parse_exec_time(
+                &["Part 1: 0 (74.13ns ± 1.2µs @ 100 samples)".into()],
+                day!(1),
+            );
+            let part_1 = res.part_1.unwrap();
+            assert_eq!(part_1.formatted, "74.13ns");
+            assert_approx_eq!(part_1.deviation_nanos.unwrap(), 1200_f64);
+            assert!(part_1.cold_nanos.is_none());
+        }
 
-        use crate::day;
+        #[test]
+        fn test_cold_run() {
+            let res = parse_exec_time(
+                &["Part 2: 0 (cold 1.5ms, warm 74.13ns ± 1.2µs @ 100 samples)".into()],
+                day!(1),
+            );
+            let part_2 = res.part_2.unwrap();
+            assert_eq!(part_2.formatted, "74.13ns");
+            assert_approx_eq!(part_2.nanos, 74.13_f64);
+            assert_approx_eq!(part_2.cold_nanos.unwrap(), 1_500_000_f64);
+            assert_eq!(part_2.samples, 100);
+        }
+
+        #[test]
+        fn test_parse_failures() {
+            let failures = parse_failures(&[
+                "Part 1: ✖ not implemented".into(),
+                "Part 2: \x1b[31m✖ failed: invalid card: `Card 1 | 2`\x1b[0m".into(),
+                "Part 1: \x1b[31m✖ panicked: oops at src/bin/05.rs:3:5\x1b[0m".into(),
+                "Parse: \x1b[31m✖ panicked: empty input at src/bin/05.rs:9:5\x1b[0m".into(),
+            ]);
+
+            assert_eq!(failures.len(), 3);
+            assert_eq!(failures[0].step, Step::Part(2));
+            assert_eq!(failures[0].message, "invalid card: `Card 1 | 2`");
+            assert!(!failures[0].panicked);
+            assert_eq!(failures[1].step, Step::Part(1));
+            assert_eq!(failures[1].message, "oops at src/bin/05.rs:3:5");
+            assert!(failures[1].panicked);
+            assert_eq!(failures[2].step, Step::Parse);
+            assert!(failures[2].panicked);
+        }
+
+        #[test]
+        fn test_parse_heap() {
+            let heap = parse_heap(&[
+                "Part 1: 0 (74.13ns)".into(),
+                "Part 1 heap: 1024 bytes in 2 blocks, 512 bytes at peak".into(),
+                "Part 2: 10 (74.13ms)".into(),
+                "Part 2 heap: 10 bytes in 1 blocks, 10 bytes at peak".into(),
+            ])
+            .unwrap();
+
+            assert_eq!(heap.total_bytes, 1034);
+            assert_eq!(heap.total_blocks, 3);
+            assert_eq!(heap.peak_bytes, 512);
+            assert!(parse_heap(&["Part 1: 0 (74.13ns)".into()]).is_none());
+        }
 
         #[test]
         fn test_well_formed() {
@@ -217,8 +632,47 @@ mod child_commands {
                 day!(1),
             );
             assert_approx_eq!(res.total_nanos, 74130074.13_f64);
-            assert_eq!(res.part_1.unwrap(), "74.13ns");
-            assert_eq!(res.part_2.unwrap(), "74.13ms");
+            assert_eq!(res.part_1.as_ref().unwrap().formatted, "74.13ns");
+            assert_eq!(res.part_1.unwrap().samples, 100000);
+            assert_eq!(res.part_2.as_ref().unwrap().formatted, "74.13ms");
+            assert_eq!(res.part_2.unwrap().samples, 99999);
+        }
+
+        #[test]
+        fn test_raw_timings() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 0 (cold 1.5ms, warm 74.13ns ± 1.2µs @ 100 samples)".into(),
+                    "Part 1 nanos: 74134 1234 1500007".into(),
+                    "Part 2: 0 (74.13ms @ 99999 samples)".into(),
+                    "Part 2 nanos: 74130000 0 74130000".into(),
+                    "".into(),
+                ],
+                day!(1),
+            );
+            let part_1 = res.part_1.unwrap();
+            assert_eq!(part_1.formatted, "74.13ns");
+            assert_approx_eq!(part_1.nanos, 74134_f64);
+            assert_approx_eq!(part_1.deviation_nanos.unwrap(), 1234_f64);
+            assert_approx_eq!(part_1.cold_nanos.unwrap(), 1500007_f64);
+            assert!(res.part_2.unwrap().deviation_nanos.is_none());
+            assert_approx_eq!(res.total_nanos, 74204134_f64);
+        }
+
+        #[test]
+        fn test_parse_step() {
+            let res = parse_exec_time(
+                &[
+                    "Parse: (1.5µs @ 100 samples)".into(),
+                    "Part 1: 0 (74.13ns @ 100000 samples)".into(),
+                    "".into(),
+                ],
+                day!(1),
+            );
+            assert_approx_eq!(res.total_nanos, 1574.13_f64);
+            assert_eq!(res.parse.unwrap().formatted, "1.5µs");
+            assert_eq!(res.part_1.unwrap().formatted, "74.13ns");
+            assert_eq!(res.part_2.is_none(), true);
         }
 
         #[test]
@@ -232,8 +686,8 @@ mod child_commands {
                 day!(1),
             );
             assert_approx_eq!(res.total_nanos, 2100000000_f64);
-            assert_eq!(res.part_1.unwrap(), "2s");
-            assert_eq!(res.part_2.unwrap(), "100ms");
+            assert_eq!(res.part_1.unwrap().formatted, "2s");
+            assert_eq!(res.part_2.unwrap().formatted, "100ms");
         }
 
         #[test]
@@ -252,3 +706,57 @@ mod child_commands {
         }
     }
 }
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{validate, Format, Options};
+
+    #[test]
+    fn rejects_ignored_options() {
+        let report = Some(PathBuf::from("target/report"));
+
+        assert!(validate(&Options::default()).is_ok());
+        assert!(validate(&Options {
+            report: report.clone(),
+            ..Options::default()
+        })
+        .is_err());
+        assert!(validate(&Options {
+            report: report.clone(),
+            time: true,
+            ..Options::default()
+        })
+        .is_ok());
+        assert!(validate(&Options {
+            report,
+            import: Some(PathBuf::from("timings.csv")),
+            ..Options::default()
+        })
+        .is_ok());
+
+        let export = Some((Format::Csv, PathBuf::from("timings.csv")));
+        assert!(validate(&Options {
+            export: export.clone(),
+            ..Options::default()
+        })
+        .is_err());
+        assert!(validate(&Options {
+            export,
+            time: true,
+            ..Options::default()
+        })
+        .is_ok());
+        assert!(validate(&Options {
+            baseline: Some("last".into()),
+            ..Options::default()
+        })
+        .is_err());
+        assert!(validate(&Options {
+            save_baseline: Some("before".into()),
+            ..Options::default()
+        })
+        .is_err());
+    }
+}
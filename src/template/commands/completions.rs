@@ -0,0 +1,491 @@
+/// Generates completion scripts for the cargo aliases of this template.
+/// The scripts wrap the existing completions for `cargo`, so built-in cargo commands keep completing as before.
+use std::{fmt::Write, process};
+
+use crate::template::cli::{Command, Flag, Values, COMMANDS};
+
+/// Cargo aliases that do not map to a command of their own.
+const EXTRA_ALIASES: &[(&str, &str)] = &[("time", "Benchmark all solutions and update the readme")];
+
+pub fn handle(shell: &str) {
+    let script = match shell {
+        "bash" => bash(),
+        "zsh" => zsh(),
+        "fish" => fish(),
+        _ => {
+            eprintln!("Unsupported shell \"{shell}\", expecting one of: bash, zsh, fish.");
+            process::exit(1);
+        }
+    };
+
+    print!("{script}");
+}
+
+fn commands() -> impl Iterator<Item = &'static Command> {
+    // `help` is not exposed as a cargo alias, `cargo help` is cargo's own command.
+    COMMANDS.iter().filter(|c| c.name != "help")
+}
+
+fn command_names() -> Vec<&'static str> {
+    commands()
+        .map(|c| c.name)
+        .chain(EXTRA_ALIASES.iter().map(|(name, _)| *name))
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn bash_values(values: Values) -> String {
+    match values {
+        Values::SolvedDays => "$(_advent_of_code_days)".into(),
+        Values::UnsolvedDays => "$(_advent_of_code_new_days)".into(),
+        Values::Commands => command_names().join(" "),
+        Values::OneOf(values) => values.join(" "),
+        Values::Path | Values::Any => String::new(),
+    }
+}
+
+fn bash() -> String {
+    let mut s = String::from(
+        r#"# bash completions for the advent of code cargo aliases.
+# Usage: source <(cargo completions bash)
+
+_advent_of_code_days() {
+    local f
+    for f in src/bin/[0-9][0-9].rs; do
+        [[ -e "$f" ]] || continue
+        f="${f#src/bin/}"
+        echo "${f%.rs}"
+    done
+}
+
+_advent_of_code_new_days() {
+    local day
+    for day in 01 02 03 04 05 06 07 08 09 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25; do
+        [[ -e "src/bin/$day.rs" ]] || echo "$day"
+    done
+}
+
+if ! complete -p cargo &>/dev/null && declare -F _completion_loader &>/dev/null; then
+    _completion_loader cargo
+fi
+_advent_of_code_fallback="$(complete -p cargo 2>/dev/null | sed -n 's/.*-F \([^ ]*\).*/\1/p')"
+
+_advent_of_code() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+
+    case "${COMP_WORDS[1]}" in
+"#,
+    );
+
+    for command in commands() {
+        let _ = writeln!(s, "        {})", command.name);
+
+        let with_values: Vec<&Flag> = command.flags.iter().filter(|f| f.value.is_some()).collect();
+        if !with_values.is_empty() {
+            let _ = writeln!(s, "            case \"$prev\" in");
+            for flag in with_values {
+                let (_, values) = flag.value.unwrap();
+                let reply = match values {
+                    Values::Path => "COMPREPLY=($(compgen -f -- \"$cur\"))".to_string(),
+                    values => format!(
+                        "COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+                        bash_values(values)
+                    ),
+                };
+                let _ = writeln!(s, "                {}) {reply}; return ;;", flag.name);
+            }
+            let _ = writeln!(s, "            esac");
+        }
+
+        let words: Vec<String> = command
+            .positional
+            .iter()
+            .map(|p| bash_values(p.values))
+            .chain(command.flags.iter().map(|f| f.name.to_string()))
+            .chain(["--help".to_string()])
+            .collect();
+
+        let _ = writeln!(
+            s,
+            "            COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+            words.join(" ")
+        );
+        let _ = writeln!(s, "            return ;;");
+    }
+
+    let _ = write!(
+        s,
+        r#"    esac
+
+    if [[ -n "$_advent_of_code_fallback" ]]; then
+        "$_advent_of_code_fallback" "$@"
+    fi
+
+    if [[ "$COMP_CWORD" -eq 1 ]]; then
+        COMPREPLY+=($(compgen -W "{}" -- "$cur"))
+    fi
+}}
+
+complete -F _advent_of_code -o bashdefault -o default cargo
+"#,
+        command_names().join(" ")
+    );
+
+    s
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn zsh_values(values: Values) -> String {
+    match values {
+        Values::SolvedDays => "($(_advent_of_code_days))".into(),
+        Values::UnsolvedDays => "($(_advent_of_code_new_days))".into(),
+        Values::Commands => format!("({})", command_names().join(" ")),
+        Values::OneOf(values) => format!("({})", values.join(" ")),
+        Values::Path => "_files".into(),
+        Values::Any => " ".into(),
+    }
+}
+
+fn zsh() -> String {
+    let mut s = String::from(
+        r#"# zsh completions for the advent of code cargo aliases.
+# Usage: source <(cargo completions zsh)
+
+_advent_of_code_days() {
+    local f
+    for f in src/bin/[0-9][0-9].rs(N); do
+        print -- "${f:t:r}"
+    done
+}
+
+_advent_of_code_new_days() {
+    local day
+    for day in {01..25}; do
+        [[ -e "src/bin/$day.rs" ]] || print -- "$day"
+    done
+}
+
+_advent_of_code() {
+    local -a commands
+    commands=(
+"#,
+    );
+
+    for command in commands() {
+        let _ = writeln!(s, "        '{}:{}'", command.name, command.about);
+    }
+    for (name, about) in EXTRA_ALIASES {
+        let _ = writeln!(s, "        '{name}:{about}'");
+    }
+
+    s.push_str(
+        r#"    )
+
+    if (( CURRENT == 2 )); then
+        _describe -t advent-of-code-commands 'advent of code command' commands
+        (( $+functions[_cargo] )) && _cargo "$@"
+        return
+    fi
+
+    case "$words[2]" in
+"#,
+    );
+
+    for command in commands() {
+        let _ = writeln!(s, "        {})", command.name);
+        let _ = writeln!(s, "            shift words");
+        let _ = writeln!(s, "            (( CURRENT-- ))");
+        let _ = writeln!(s, "            _arguments \\");
+
+        for flag in command.flags {
+            match flag.value {
+                Some((name, values)) => {
+                    let _ = writeln!(
+                        s,
+                        "                \"{}[{}]:{name}:{}\" \\",
+                        flag.name,
+                        flag.help,
+                        zsh_values(values)
+                    );
+                }
+                None => {
+                    let _ = writeln!(s, "                \"{}[{}]\" \\", flag.name, flag.help);
+                }
+            }
+        }
+
+        let _ = write!(
+            s,
+            "                \"(-h --help)\"{{-h,--help}}\"[Print help]\""
+        );
+        if let Some(positional) = &command.positional {
+            let _ = write!(
+                s,
+                " \\\n                \"1:{}:{}\"",
+                positional.name,
+                zsh_values(positional.values)
+            );
+        }
+        let _ = writeln!(s);
+        let _ = writeln!(s, "            ;;");
+    }
+
+    s.push_str(
+        r#"        *)
+            (( $+functions[_cargo] )) && _cargo "$@"
+            ;;
+    esac
+}
+
+compdef _advent_of_code cargo
+"#,
+    );
+
+    s
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn fish_values(values: Values) -> String {
+    match values {
+        Values::SolvedDays => "-f -a '(__advent_of_code_days)'".into(),
+        Values::UnsolvedDays => "-f -a '(__advent_of_code_new_days)'".into(),
+        Values::Commands => format!("-f -a '{}'", command_names().join(" ")),
+        Values::OneOf(values) => format!("-f -a '{}'", values.join(" ")),
+        Values::Path => "-F".into(),
+        Values::Any => "-f".into(),
+    }
+}
+
+fn fish() -> String {
+    let mut s = String::from(
+        r"# fish completions for the advent of code cargo aliases.
+# Usage: cargo completions fish | source
+
+function __advent_of_code_days
+    for f in src/bin/[0-9][0-9].rs
+        string replace -r '^src/bin/(\d\d)\.rs$' '$1' -- $f
+    end
+end
+
+function __advent_of_code_new_days
+    for day in (seq -w 1 25)
+        test -e src/bin/$day.rs; or echo $day
+    end
+end
+
+",
+    );
+
+    for command in commands() {
+        let _ = writeln!(
+            s,
+            "complete -c cargo -n __fish_use_subcommand -f -a {} -d '{}'",
+            command.name, command.about
+        );
+    }
+    for (name, about) in EXTRA_ALIASES {
+        let _ = writeln!(
+            s,
+            "complete -c cargo -n __fish_use_subcommand -f -a {name} -d '{about}'"
+        );
+    }
+
+    for command in commands() {
+        let _ = writeln!(s);
+        let condition = format!("-n '__fish_seen_subcommand_from {}'", command.name);
+
+        if let Some(positional) = &command.positional {
+            let _ = writeln!(
+                s,
+                "complete -c cargo {condition} {} -d '{}'",
+                fish_values(positional.values),
+                positional.help
+            );
+        }
+
+        for flag in command.flags {
+            let long = flag.name.trim_start_matches("--");
+            let values = match flag.value {
+                Some((_, values)) => format!(" -r {}", fish_values(values)),
+                None => String::new(),
+            };
+            let _ = writeln!(
+                s,
+                "complete -c cargo {condition} -l {long}{values} -d '{}'",
+                flag.help
+            );
+        }
+    }
+
+    s
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{bash, fish, zsh};
+
+    const SOLVE_FLAGS: &[&str] = &[
+        "--release",
+        "--time",
+        "--bench-time",
+        "--min-samples",
+        "--max-samples",
+        "--warmup",
+        "--threads",
+        "--scaling",
+        "--dhat",
+        "--profile-cpu",
+        "--visualize",
+        "--frames",
+        "--frame-format",
+        "--verbose",
+        "--submit",
+        "--watch",
+        "--test",
+        "--input",
+        "--variant",
+        "--compare",
+        "--generated",
+    ];
+
+    /// Lines of the script from the first line that equals `start` up to the next line that starts with `end`.
+    fn section<'a>(script: &'a str, start: &str, end: &str) -> Vec<&'a str> {
+        script
+            .lines()
+            .skip_while(|l| *l != start)
+            .skip(1)
+            .take_while(|l| !l.starts_with(end))
+            .collect()
+    }
+
+    fn assert_lines(script: &str, lines: &[&str]) {
+        for line in lines {
+            assert!(
+                script.lines().any(|l| l == *line),
+                "{line} missing in:\n{script}"
+            );
+        }
+    }
+
+    #[test]
+    fn bash_completes_flags_and_values() {
+        let script = bash();
+
+        assert_eq!(
+            section(&script, "        scaffold)", "        download)"),
+            [
+                "            COMPREPLY=($(compgen -W \"$(_advent_of_code_new_days) --download --help\" -- \"$cur\"))",
+                "            return ;;",
+            ]
+        );
+
+        let solve_words = format!(
+            "            COMPREPLY=($(compgen -W \"$(_advent_of_code_days) {} --help\" -- \"$cur\"))",
+            SOLVE_FLAGS.join(" ")
+        );
+
+        assert_lines(
+            &script,
+            &[
+                &solve_words,
+                "                --submit) COMPREPLY=($(compgen -W \"1 2\" -- \"$cur\")); return ;;",
+                "                --frame-format) COMPREPLY=($(compgen -W \"png ppm\" -- \"$cur\")); return ;;",
+                "                --input) COMPREPLY=($(compgen -f -- \"$cur\")); return ;;",
+                "                --export) COMPREPLY=($(compgen -W \"csv json\" -- \"$cur\")); return ;;",
+                "            COMPREPLY=($(compgen -W \"bash zsh fish --help\" -- \"$cur\"))",
+                "        COMPREPLY+=($(compgen -W \"scaffold download read solve gen all completions time\" -- \"$cur\"))",
+            ],
+        );
+    }
+
+    #[test]
+    fn zsh_completes_flags_and_values() {
+        let script = zsh();
+
+        assert_eq!(
+            section(&script, "    commands=(", "    )"),
+            [
+                "        'scaffold:Create the solution, input and example files for a day'",
+                "        'download:Download the input and puzzle description for a day via aoc-cli'",
+                "        'read:Print the puzzle description for a day via aoc-cli'",
+                "        'solve:Run the solution for a day'",
+                "        'gen:Write a generated input for a day to data/generated'",
+                "        'all:Run the solutions for all days'",
+                "        'completions:Print a shell completion script'",
+                "        'time:Benchmark all solutions and update the readme'",
+            ]
+        );
+
+        assert_eq!(
+            section(&script, "        scaffold)", "        download)"),
+            [
+                "            shift words",
+                "            (( CURRENT-- ))",
+                "            _arguments \\",
+                "                \"--download[Download the input and puzzle description after scaffolding]\" \\",
+                "                \"(-h --help)\"{-h,--help}\"[Print help]\" \\",
+                "                \"1:day:($(_advent_of_code_new_days))\"",
+                "            ;;",
+            ]
+        );
+
+        let solve_flags: Vec<&str> = section(&script, "        solve)", "        gen)")
+            .iter()
+            .filter_map(|l| l.trim_start().strip_prefix("\"--"))
+            .map(|l| &l[..l.find('[').unwrap()])
+            .collect();
+        assert_eq!(
+            solve_flags,
+            SOLVE_FLAGS
+                .iter()
+                .map(|f| f.trim_start_matches("--"))
+                .collect::<Vec<_>>()
+        );
+
+        assert_lines(
+            &script,
+            &[
+                "                \"--submit[Submit the answer of the given part via aoc-cli]:part:(1 2)\" \\",
+                "                \"--frame-format[Image format of the frames written with --frames, defaults to png]:format:(png ppm)\" \\",
+                "                \"--input[Read the input from the given file instead, - reads from stdin]:path:_files\" \\",
+                "                \"1:shell:(bash zsh fish)\"",
+            ],
+        );
+    }
+
+    #[test]
+    fn fish_completes_flags_and_values() {
+        let script = fish();
+        let solve = "complete -c cargo -n '__fish_seen_subcommand_from solve'";
+
+        let solve_flags: Vec<&str> = script
+            .lines()
+            .filter_map(|l| l.strip_prefix(solve)?.strip_prefix(" -l "))
+            .map(|l| l.split(' ').next().unwrap())
+            .collect();
+        assert_eq!(
+            solve_flags,
+            SOLVE_FLAGS
+                .iter()
+                .map(|f| f.trim_start_matches("--"))
+                .collect::<Vec<_>>()
+        );
+
+        assert_lines(
+            &script,
+            &[
+                "complete -c cargo -n __fish_use_subcommand -f -a time -d 'Benchmark all solutions and update the readme'",
+                "complete -c cargo -n '__fish_seen_subcommand_from scaffold' -f -a '(__advent_of_code_new_days)' -d 'Day number between 1 and 25, today or yesterday'",
+                &format!("{solve} -f -a '(__advent_of_code_days)' -d 'Day number between 1 and 25, today or yesterday'"),
+                &format!("{solve} -l submit -r -f -a '1 2' -d 'Submit the answer of the given part via aoc-cli'"),
+                &format!("{solve} -l input -r -F -d 'Read the input from the given file instead, - reads from stdin'"),
+                &format!("{solve} -l release -d 'Run an optimized build'"),
+                "complete -c cargo -n '__fish_seen_subcommand_from completions' -f -a 'bash zsh fish' -d 'One of bash, zsh or fish'",
+            ],
+        );
+    }
+}
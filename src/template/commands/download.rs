@@ -1,13 +1,13 @@
-use crate::template::{aoc_cli, Day};
+use crate::template::{aoc_cli, config::Config, Day};
 use std::process;
 
-pub fn handle(day: Day) {
+pub fn handle(day: Day, config: &Config) {
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
     }
 
-    if let Err(e) = aoc_cli::download(day) {
+    if let Err(e) = aoc_cli::download(day, config) {
         eprintln!("failed to call aoc-cli: {e}");
         process::exit(1);
     };
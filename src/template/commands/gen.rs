@@ -0,0 +1,37 @@
+use std::{
+    fs,
+    process::{self, Command, Stdio},
+};
+
+use crate::template::{config::Config, Day};
+
+pub fn handle(day: Day, scale: usize, config: &Config) {
+    let output = match Command::new("cargo")
+        .args(["run", "--quiet", "--release", "--bin", &day.to_string()])
+        .args(["--", "--generate", &scale.to_string()])
+        .stderr(Stdio::inherit())
+        .output()
+    {
+        Ok(output) if output.status.success() => output.stdout,
+        Ok(_) => process::exit(1),
+        Err(e) => {
+            eprintln!("Failed to run the solution of day {day}: {e}");
+            process::exit(1);
+        }
+    };
+
+    let path = config.generated_path(day, scale);
+
+    if let Err(e) = path.parent().map_or(Ok(()), fs::create_dir_all) {
+        eprintln!("Failed to create \"{}\": {e}", path.display());
+        process::exit(1);
+    }
+
+    match fs::write(&path, output) {
+        Ok(()) => println!("Wrote generated input to \"{}\"", path.display()),
+        Err(e) => {
+            eprintln!("Failed to write \"{}\": {e}", path.display());
+            process::exit(1);
+        }
+    }
+}
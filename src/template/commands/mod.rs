@@ -1,5 +1,7 @@
 pub mod all;
+pub mod completions;
 pub mod download;
+pub mod gen;
 pub mod read;
 pub mod scaffold;
 pub mod solve;
@@ -1,26 +1,47 @@
 use std::{
-    fs::{File, OpenOptions},
+    borrow::Cow,
+    fs::{self, File, OpenOptions},
     io::Write,
+    path::Path,
     process,
 };
 
-use crate::template::Day;
+use crate::template::{config::Config, Day};
 
 const MODULE_TEMPLATE: &str =
     include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
 
-fn safe_create_file(path: &str) -> Result<File, std::io::Error> {
+fn safe_create_file(path: &Path) -> Result<File, std::io::Error> {
     OpenOptions::new().write(true).create_new(true).open(path)
 }
 
-fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new().write(true).create(true).open(path)
+fn create_file(path: &Path) -> Result<File, std::io::Error> {
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
 }
 
-pub fn handle(day: Day) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+fn read_template(config: &Config) -> Cow<'static, str> {
+    let Some(path) = &config.template else {
+        return Cow::Borrowed(MODULE_TEMPLATE);
+    };
+
+    match fs::read_to_string(path) {
+        Ok(template) => Cow::Owned(template),
+        Err(e) => {
+            eprintln!("Failed to read template \"{}\": {e}", path.display());
+            process::exit(1);
+        }
+    }
+}
+
+pub fn handle(day: Day, config: &Config) {
+    let input_path = config.input_path(day);
+    let example_path = config.example_path(day);
+    let module_path = config.bin_path(day);
+    let template = read_template(config);
 
     let mut file = match safe_create_file(&module_path) {
         Ok(file) => file,
@@ -31,12 +52,12 @@ pub fn handle(day: Day) {
     };
 
     match file.write_all(
-        MODULE_TEMPLATE
+        template
             .replace("%DAY_NUMBER%", &day.into_inner().to_string())
             .as_bytes(),
     ) {
         Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
+            println!("Created module file \"{}\"", module_path.display());
         }
         Err(e) => {
             eprintln!("Failed to write module contents: {e}");
@@ -46,7 +67,7 @@ pub fn handle(day: Day) {
 
     match create_file(&input_path) {
         Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
+            println!("Created empty input file \"{}\"", input_path.display());
         }
         Err(e) => {
             eprintln!("Failed to create input file: {e}");
@@ -56,7 +77,7 @@ pub fn handle(day: Day) {
 
     match create_file(&example_path) {
         Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
+            println!("Created empty example file \"{}\"", example_path.display());
         }
         Err(e) => {
             eprintln!("Failed to create example file: {e}");
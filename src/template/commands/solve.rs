@@ -1,38 +1,507 @@
-use std::process::{Command, Stdio};
+use std::process::{self, Command, Stdio};
 
-use crate::template::Day;
+use super::all::child_commands;
+use crate::template::{
+    config::{BenchSettings, Config},
+    generate, log, scaling, visualize, Day, ANSI_BOLD, ANSI_RESET,
+};
+
+/// Options of the `solve` command.
+#[derive(Default)]
+pub struct Options {
+    pub release: bool,
+    pub time: bool,
+    pub dhat: bool,
+    /// Profile each part with a sampling profiler and write flamegraphs.
+    pub profile_cpu: bool,
+    pub submit: Option<u8>,
+    pub watch: bool,
+    pub test: bool,
+    /// Path of a custom input file, `-` reads the input from stdin.
+    pub input: Option<String>,
+    /// Benchmark settings that are passed to the solution with `--time`.
+    pub bench: BenchSettings,
+    /// Size of the rayon thread pool the solution runs with.
+    pub threads: Option<usize>,
+    /// Benchmark the solution with an increasing number of threads.
+    pub scaling: bool,
+    /// Draw the frames that the solution emits to the terminal or a directory.
+    pub visualize: Option<visualize::Backend>,
+    /// Print log messages of the solution: 1 for debug messages, 2 for trace messages as well.
+    pub verbose: u8,
+    /// Benchmark the solution on generated inputs of increasing scale, up to this one.
+    pub generated: Option<usize>,
+    /// Name of the variant that runs instead of the default solution of each part that has one.
+    pub variant: Option<String>,
+    /// Benchmark all variants of each part and check that they agree.
+    pub compare: bool,
+}
+
+pub fn handle(day: Day, mut options: Options, config: &Config) {
+    if options.dhat && options.profile_cpu {
+        eprintln!("`--dhat` and `--profile-cpu` cannot be combined.");
+        process::exit(1);
+    }
+
+    if options.visualize.is_some()
+        && (options.time || options.scaling || options.dhat || options.profile_cpu || options.test)
+    {
+        eprintln!("Visualizing cannot be combined with `--time`, `--scaling`, `--dhat`, `--profile-cpu` or `--test`.");
+        process::exit(1);
+    }
+
+    if options.compare
+        && (options.time
+            || options.scaling
+            || options.generated.is_some()
+            || options.dhat
+            || options.profile_cpu
+            || options.visualize.is_some()
+            || options.submit.is_some()
+            || options.test
+            || options.variant.is_some())
+    {
+        eprintln!("`--compare` benchmarks all variants and cannot be combined with `--time`, `--scaling`, `--generated`, `--dhat`, `--profile-cpu`, visualizing, `--submit`, `--test` or `--variant`.");
+        process::exit(1);
+    }
+
+    if options.watch {
+        if options.submit.take().is_some() {
+            eprintln!("Warning: `--submit` is ignored in watch mode.");
+        }
+
+        if options.input.as_deref() == Some("-") {
+            eprintln!("Reading input from stdin is not supported in watch mode.");
+            process::exit(1);
+        }
+    }
+
+    if options.scaling {
+        run_scaling(day, &options, config);
+        return;
+    }
+
+    if let Some(max_scale) = options.generated {
+        run_growth(day, max_scale, &options, config);
+        return;
+    }
+
+    let cmd_args = build_args(day, &options);
+
+    if options.watch {
+        watch::run(day, &cmd_args, options.input.as_deref(), config);
+        return;
+    }
+
+    let mut cmd = Command::new("cargo")
+        .args(&cmd_args)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .unwrap();
+
+    // e.g. variants that disagree, so scripts can rely on the exit status.
+    let status = cmd.wait().unwrap();
+    if !status.success() {
+        process::exit(status.code().unwrap_or(1));
+    }
+}
+
+fn build_args(day: Day, options: &Options) -> Vec<String> {
+    if options.test {
+        let mut cmd_args = vec!["test".to_string(), "--bin".to_string(), day.to_string()];
+        if options.release {
+            cmd_args.push("--release".to_string());
+        }
+        return cmd_args;
+    }
 
-pub fn handle(day: Day, release: bool, time: bool, dhat: bool, submit_part: Option<u8>) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
-    if dhat {
+    if options.dhat {
         cmd_args.extend([
             "--profile".to_string(),
             "dhat".to_string(),
             "--features".to_string(),
             "dhat-heap".to_string(),
         ]);
-    } else if release {
+    } else if options.profile_cpu {
+        cmd_args.extend([
+            "--profile".to_string(),
+            "flamegraph".to_string(),
+            "--features".to_string(),
+            "cpu-profile".to_string(),
+        ]);
+    } else if options.release {
         cmd_args.push("--release".to_string());
     }
 
+    // logging is compiled out of optimized builds unless the feature is enabled.
+    if options.verbose > 0 && (options.release || options.dhat || options.profile_cpu) {
+        cmd_args.push("--features".to_string());
+        cmd_args.push("debug-log".to_string());
+    }
+
     cmd_args.push("--".to_string());
 
-    if let Some(submit_part) = submit_part {
+    if let Some(submit_part) = options.submit {
         cmd_args.push("--submit".to_string());
         cmd_args.push(submit_part.to_string());
     }
 
-    if time {
+    if options.time {
         cmd_args.push("--time".to_string());
     }
 
-    let mut cmd = Command::new("cargo")
-        .args(&cmd_args)
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .spawn()
-        .unwrap();
+    if options.compare {
+        cmd_args.push("--compare".to_string());
+    }
+
+    // the profiler runs each part for the configured bench time.
+    if options.time || options.profile_cpu || options.compare {
+        cmd_args.extend(options.bench.to_args());
+    }
+
+    if let Some(threads) = options.threads {
+        cmd_args.push("--threads".to_string());
+        cmd_args.push(threads.to_string());
+    }
+
+    if let Some(input) = &options.input {
+        cmd_args.push("--input".to_string());
+        cmd_args.push(input.clone());
+    }
+
+    cmd_args.extend(variant_args(options));
+
+    if let Some(backend) = &options.visualize {
+        cmd_args.extend(backend.to_args());
+    }
+
+    cmd_args.extend(log::verbosity_args(options.verbose));
+
+    cmd_args
+}
+
+fn variant_args(options: &Options) -> Vec<String> {
+    match &options.variant {
+        Some(variant) => vec!["--variant".to_string(), variant.clone()],
+        None => vec![],
+    }
+}
+
+/// Benchmarks the solution with 1, 2, 4… threads up to the available parallelism, or `--threads` if set.
+fn run_scaling(day: Day, options: &Options, config: &Config) {
+    if options.watch
+        || options.test
+        || options.dhat
+        || options.profile_cpu
+        || options.submit.is_some()
+        || options.generated.is_some()
+    {
+        eprintln!("`--scaling` cannot be combined with `--watch`, `--test`, `--dhat`, `--profile-cpu`, `--submit` or `--generated`.");
+        process::exit(1);
+    }
+
+    if options.input.is_some() {
+        eprintln!("Warning: `--input` is ignored when measuring scaling.");
+    }
+
+    let max_threads = options.threads.unwrap_or_else(scaling::available_threads);
+    let mut runs = vec![];
+
+    for threads in scaling::thread_counts(max_threads) {
+        println!(
+            "{ANSI_BOLD}{}{ANSI_RESET}",
+            scaling::format_threads(threads)
+        );
+
+        let output = match child_commands::run_solution(
+            day,
+            Some(&options.bench),
+            Some(threads),
+            options.release,
+            false,
+            &variant_args(options),
+            config,
+        ) {
+            Ok(output) if !output.is_empty() => output,
+            _ => {
+                eprintln!("Failed to run the solution of day {day}.");
+                process::exit(1);
+            }
+        };
+
+        runs.push(scaling::ScalingRun {
+            threads,
+            timings: child_commands::parse_exec_time(&output, day),
+        });
+        println!();
+    }
+
+    scaling::print(day, &runs);
+}
+
+/// Benchmarks the solution on generated inputs of scale 1, 2, 4… up to `max_scale` and reports how its runtime grows.
+fn run_growth(day: Day, max_scale: usize, options: &Options, config: &Config) {
+    if options.watch
+        || options.test
+        || options.dhat
+        || options.profile_cpu
+        || options.submit.is_some()
+        || options.input.is_some()
+        || options.visualize.is_some()
+    {
+        eprintln!("`--generated` cannot be combined with `--watch`, `--test`, `--dhat`, `--profile-cpu`, `--submit`, `--input` or visualizing.");
+        process::exit(1);
+    }
+
+    let mut runs = vec![];
+
+    for scale in generate::scales(max_scale) {
+        println!("{ANSI_BOLD}Scale {scale}{ANSI_RESET}");
+
+        let output = match child_commands::run_solution(
+            day,
+            Some(&options.bench),
+            options.threads,
+            options.release,
+            false,
+            &[
+                vec!["--generated".into(), scale.to_string()],
+                variant_args(options),
+            ]
+            .concat(),
+            config,
+        ) {
+            Ok(output) if !output.is_empty() => output,
+            _ => {
+                eprintln!("Failed to run the solution of day {day}.");
+                process::exit(1);
+            }
+        };
+
+        let Some(bytes) = generate::parse_input_size(&output) else {
+            // the solution printed why it could not generate an input.
+            process::exit(1);
+        };
+
+        runs.push(generate::GrowthRun {
+            scale,
+            bytes,
+            timings: child_commands::parse_exec_time(&output, day),
+        });
+        println!();
+    }
+
+    generate::print(day, &runs);
+}
+
+/// Re-runs a solution (or its tests) whenever one of its source or data files changes.
+/// Changes are detected by polling modification times, which avoids platform-specific file watchers.
+mod watch {
+    use std::{
+        collections::{BTreeMap, HashMap},
+        fs,
+        io::{BufRead, BufReader},
+        path::{Path, PathBuf},
+        process::{Command, Stdio},
+        thread,
+        time::{Duration, SystemTime},
+    };
+
+    use crate::template::{
+        config::{Config, CONFIG_PATH},
+        Day, ANSI_BOLD, ANSI_CLEAR_SCREEN, ANSI_RESET, ANSI_YELLOW,
+    };
+
+    const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+    type Snapshot = BTreeMap<PathBuf, SystemTime>;
+
+    pub fn run(day: Day, cmd_args: &[String], input: Option<&str>, config: &Config) {
+        let mut previous_results: HashMap<String, String> = HashMap::new();
+
+        loop {
+            let snapshot = take_snapshot(day, input, config);
+
+            print!("{ANSI_CLEAR_SCREEN}");
+            previous_results = run_once(cmd_args, &previous_results);
+
+            println!();
+            println!(
+                "🎄 Watching {} files for changes. Press Ctrl-C to exit.",
+                snapshot.len()
+            );
+
+            wait_for_change(day, input, config, &snapshot);
+        }
+    }
+
+    fn wait_for_change(day: Day, input: Option<&str>, config: &Config, snapshot: &Snapshot) {
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            if &take_snapshot(day, input, config) != snapshot {
+                // editors frequently write files in several steps, give them time to settle.
+                thread::sleep(POLL_INTERVAL);
+                return;
+            }
+        }
+    }
+
+    /// Runs the command once, forwarding its output while highlighting results that differ from the previous run.
+    fn run_once(
+        cmd_args: &[String],
+        previous: &HashMap<String, String>,
+    ) -> HashMap<String, String> {
+        let mut results = HashMap::new();
 
-    cmd.wait().unwrap();
+        let mut cmd = match Command::new("cargo")
+            .args(cmd_args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+        {
+            Ok(cmd) => cmd,
+            Err(e) => {
+                eprintln!("Failed to spawn cargo: {e}");
+                return previous.clone();
+            }
+        };
+
+        if let Some(stdout) = cmd.stdout.take() {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                let Some((key, value)) = parse_result_line(&line) else {
+                    println!("{line}");
+                    continue;
+                };
+
+                match previous.get(&key) {
+                    Some(prev) if prev != &value => {
+                        println!(
+                            "{line} {ANSI_YELLOW}{ANSI_BOLD}(changed, was: {prev}){ANSI_RESET}"
+                        );
+                    }
+                    None if !previous.is_empty() => {
+                        println!("{line} {ANSI_YELLOW}{ANSI_BOLD}(new){ANSI_RESET}");
+                    }
+                    _ => println!("{line}"),
+                }
+
+                results.insert(key, value);
+            }
+        }
+
+        let _ = cmd.wait();
+
+        results
+    }
+
+    /// Extracts a `(key, value)` pair from either a `Part N: <result>` line printed by the runner
+    /// or a `test <name> ... <status>` line printed by the test harness.
+    fn parse_result_line(line: &str) -> Option<(String, String)> {
+        // intermediate output is overwritten with a carriage return, only keep the final state.
+        let line = line.rsplit('\r').next()?;
+
+        if let Some(rest) = line.strip_prefix("test ") {
+            let (name, status) = rest.split_once(" ... ")?;
+            return Some((name.to_string(), status.trim().to_string()));
+        }
+
+        if !line.starts_with("Part ") {
+            return None;
+        }
+
+        let (part, rest) = line.split_once(": ")?;
+
+        let value = match rest.split_once(ANSI_BOLD.code()) {
+            Some((_, result)) => result.split(ANSI_RESET.code()).next()?,
+            // unsolved parts and unrecognized multi-line results do not print in bold.
+            None => rest.split(" (").next()?,
+        };
+
+        Some((part.to_string(), value.trim().to_string()))
+    }
+
+    fn watched_paths(day: Day, input: Option<&str>, config: &Config) -> Vec<PathBuf> {
+        let input_path = input.map_or_else(|| config.input_path(day), PathBuf::from);
+
+        let mut paths = vec![
+            config.bin_path(day),
+            PathBuf::from("src/lib.rs"),
+            PathBuf::from(CONFIG_PATH),
+            input_path,
+        ];
+
+        collect_files(Path::new("src/template"), &mut paths);
+
+        // examples can be split per part, e.g. `01-2.txt`.
+        if let Ok(entries) = fs::read_dir(config.data_dir.join("examples")) {
+            paths.extend(
+                entries
+                    .filter_map(Result::ok)
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        path.file_name()
+                            .and_then(|name| name.to_str())
+                            .is_some_and(|name| name.starts_with(&day.to_string()))
+                    }),
+            );
+        }
+
+        paths
+    }
+
+    fn collect_files(dir: &Path, paths: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            if path.is_dir() {
+                collect_files(&path, paths);
+            } else {
+                paths.push(path);
+            }
+        }
+    }
+
+    fn take_snapshot(day: Day, input: Option<&str>, config: &Config) -> Snapshot {
+        watched_paths(day, input, config)
+            .into_iter()
+            .filter_map(|path| {
+                let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+                Some((path, modified))
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "test_lib")]
+    mod tests {
+        use super::parse_result_line;
+
+        #[test]
+        fn parses_part_results() {
+            let res = parse_result_line("Part 1: \x1b[1m42\x1b[0m (74.13ns @ 100000 samples)");
+            assert_eq!(res, Some(("Part 1".into(), "42".into())));
+        }
+
+        #[test]
+        fn parses_overwritten_part_results() {
+            let res = parse_result_line("Part 2: ✖\rPart 2: ✖             ");
+            assert_eq!(res, Some(("Part 2".into(), "✖".into())));
+        }
+
+        #[test]
+        fn parses_test_results() {
+            let res = parse_result_line("test tests::test_part_one ... FAILED");
+            assert_eq!(res, Some(("tests::test_part_one".into(), "FAILED".into())));
+        }
+
+        #[test]
+        fn ignores_other_lines() {
+            assert_eq!(parse_result_line("running 2 tests"), None);
+        }
+    }
 }
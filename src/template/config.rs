@@ -0,0 +1,607 @@
+/// Project configuration, loaded from `aoc.toml` in the project root.
+///
+/// All keys are optional, unknown keys are rejected.
+///
+/// ```toml
+/// year = 2023
+/// data_dir = "data"
+/// template = "src/template.txt"
+/// color = true
+///
+/// [readme]
+/// path = "README.md"
+/// marker = "<!--- benchmarking table --->"
+/// columns = ["day", "part_1", "part_2"]
+/// sort = "day"
+///
+/// [bench]
+/// time = "1s"
+/// min_samples = 10
+/// max_samples = 10000
+/// warmup = "100ms"
+///
+/// [input]
+/// normalize_line_endings = false
+/// strip_bom = false
+/// trailing_newline = "keep"
+/// ```
+use std::{
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+    process,
+    sync::OnceLock,
+    time::Duration,
+};
+
+use serde::Deserialize;
+use toml::Spanned;
+
+use crate::template::{
+    input::Normalization,
+    readme_benchmarks::{Column, Sort},
+    Day,
+};
+
+pub const CONFIG_PATH: &str = "aoc.toml";
+
+const DEFAULT_README_MARKER: &str = "<!--- benchmarking table --->";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// Directory containing the `inputs`, `examples` and `puzzles` folders.
+    pub data_dir: PathBuf,
+    /// The year of advent that is solved. Omitting it lets `aoc-cli` pick the current (or last) event.
+    pub year: Option<u16>,
+    pub readme_path: PathBuf,
+    /// Marker that encloses the benchmark table in the readme.
+    pub readme_marker: String,
+    pub readme_columns: Vec<Column>,
+    pub readme_sort: Sort,
+    pub bench: BenchSettings,
+    /// Normalizations applied to inputs and examples when they are loaded.
+    pub input: Normalization,
+    /// Template used by `scaffold`. The built-in template is used if this is not set.
+    pub template: Option<PathBuf>,
+    /// Whether output is colored. Defaults to `false` if the `NO_COLOR` environment variable is set.
+    pub color: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            data_dir: PathBuf::from("data"),
+            year: None,
+            readme_path: PathBuf::from("README.md"),
+            readme_marker: DEFAULT_README_MARKER.into(),
+            readme_columns: vec![Column::Day, Column::Part1, Column::Part2],
+            readme_sort: Sort::Day,
+            bench: BenchSettings::default(),
+            input: Normalization::default(),
+            template: None,
+            color: std::env::var_os("NO_COLOR").is_none(),
+        }
+    }
+}
+
+impl Config {
+    /// Reads the configuration from the given path, falling back to defaults if the file does not exist.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        match fs::read_to_string(path) {
+            Ok(s) => Self::parse(&s),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(Error::IO(e)),
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let file: File = toml::from_str(s).map_err(|e| Error::Syntax {
+            line: e.span().map_or(1, |span| line_of(s, span.start)),
+            message: e.message().into(),
+        })?;
+
+        let mut config = Self::default();
+
+        if let Some(year) = &file.year {
+            config.year = Some(check(s, "year", year, |&year| {
+                if (2015..=9999).contains(&year) {
+                    Ok(year)
+                } else {
+                    Err("expecting a year of advent, e.g. `2023`".into())
+                }
+            })?);
+        }
+        if let Some(data_dir) = file.data_dir {
+            config.data_dir = data_dir;
+        }
+        config.template = file.template;
+        if let Some(color) = file.color {
+            config.color = color;
+        }
+
+        let readme = file.readme;
+        if let Some(path) = readme.path {
+            config.readme_path = path;
+        }
+        if let Some(marker) = &readme.marker {
+            config.readme_marker = check(s, "readme.marker", marker, |marker| {
+                if marker.is_empty() {
+                    Err("the marker must not be empty".into())
+                } else {
+                    Ok(marker.clone())
+                }
+            })?;
+        }
+        if let Some(columns) = &readme.columns {
+            config.readme_columns = check(s, "readme.columns", columns, |columns| {
+                if columns.is_empty() {
+                    return Err("expecting at least one column".into());
+                }
+                columns.iter().map(|c| c.parse()).collect()
+            })?;
+        }
+        if let Some(sort) = &readme.sort {
+            config.readme_sort = check(s, "readme.sort", sort, |sort| sort.parse())?;
+        }
+
+        let bench = file.bench;
+        if let Some(time) = &bench.time {
+            config.bench.time = check(s, "bench.time", time, |time| parse_duration(time))?;
+        }
+        if let Some(min_samples) = &bench.min_samples {
+            config.bench.min_samples = check(s, "bench.min_samples", min_samples, |&n| {
+                positive_samples(n)
+            })?;
+        }
+        if let Some(max_samples) = &bench.max_samples {
+            config.bench.max_samples = check(s, "bench.max_samples", max_samples, |&n| {
+                positive_samples(n)
+            })?;
+        }
+        if let Some(warmup) = &bench.warmup {
+            config.bench.warmup =
+                check(s, "bench.warmup", warmup, |warmup| parse_duration(warmup))?;
+        }
+
+        // the sample bounds depend on each other, a conflict is reported at the bound that comes last.
+        let last_bound = [
+            ("bench.min_samples", &bench.min_samples),
+            ("bench.max_samples", &bench.max_samples),
+        ]
+        .into_iter()
+        .filter_map(|(key, samples)| Some((key, samples.as_ref()?)))
+        .max_by_key(|(_, samples)| samples.span().start);
+        if let Some((key, samples)) = last_bound {
+            check(s, key, samples, |_| config.bench.validate())?;
+        }
+
+        let input = file.input;
+        if let Some(line_endings) = input.normalize_line_endings {
+            config.input.line_endings = line_endings;
+        }
+        if let Some(strip_bom) = input.strip_bom {
+            config.input.strip_bom = strip_bom;
+        }
+        if let Some(trailing_newline) = &input.trailing_newline {
+            config.input.trailing_newline =
+                check(s, "input.trailing_newline", trailing_newline, |t| t.parse())?;
+        }
+
+        Ok(config)
+    }
+
+    /// Path of the puzzle input for a day, e.g. `data/inputs/01.txt`.
+    #[must_use]
+    pub fn input_path(&self, day: Day) -> PathBuf {
+        self.data_dir.join("inputs").join(format!("{day}.txt"))
+    }
+
+    /// Path of the example input for a day, e.g. `data/examples/01.txt`.
+    #[must_use]
+    pub fn example_path(&self, day: Day) -> PathBuf {
+        self.data_dir.join("examples").join(format!("{day}.txt"))
+    }
+
+    /// Path of the puzzle description for a day, e.g. `data/puzzles/01.md`.
+    #[must_use]
+    pub fn puzzle_path(&self, day: Day) -> PathBuf {
+        self.data_dir.join("puzzles").join(format!("{day}.md"))
+    }
+
+    /// Path of a generated input for a day, e.g. `data/generated/01-4.txt` for scale 4.
+    #[must_use]
+    pub fn generated_path(&self, day: Day, scale: usize) -> PathBuf {
+        self.data_dir
+            .join("generated")
+            .join(format!("{day}-{scale}.txt"))
+    }
+
+    /// Path of the solution binary for a day. This is not configurable because cargo discovers binaries in `src/bin`.
+    #[must_use]
+    pub fn bin_path(&self, day: Day) -> PathBuf {
+        PathBuf::from("src").join("bin").join(format!("{day}.rs"))
+    }
+}
+
+/// The contents of `aoc.toml`. Values that need validation keep their position, to report the line they are on.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct File {
+    year: Option<Spanned<u16>>,
+    data_dir: Option<PathBuf>,
+    template: Option<PathBuf>,
+    color: Option<bool>,
+    readme: ReadmeSection,
+    bench: BenchSection,
+    input: InputSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ReadmeSection {
+    path: Option<PathBuf>,
+    marker: Option<Spanned<String>>,
+    columns: Option<Spanned<Vec<String>>>,
+    sort: Option<Spanned<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct BenchSection {
+    time: Option<Spanned<String>>,
+    min_samples: Option<Spanned<u64>>,
+    max_samples: Option<Spanned<u64>>,
+    warmup: Option<Spanned<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct InputSection {
+    normalize_line_endings: Option<bool>,
+    strip_bom: Option<bool>,
+    trailing_newline: Option<Spanned<String>>,
+}
+
+/// Parameters of benchmarks run with `--time`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchSettings {
+    /// Approximate wall time spent benchmarking a single part.
+    pub time: Duration,
+    pub min_samples: u128,
+    pub max_samples: u128,
+    /// Time spent running a part before it is benchmarked. The warmup runs determine the number of samples.
+    pub warmup: Duration,
+}
+
+impl Default for BenchSettings {
+    fn default() -> Self {
+        Self {
+            time: Duration::from_secs(1),
+            min_samples: 10,
+            max_samples: 10000,
+            warmup: Duration::from_millis(100),
+        }
+    }
+}
+
+impl Display for BenchSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} per part, {} to {} samples, {:?} warmup",
+            self.time, self.min_samples, self.max_samples, self.warmup
+        )
+    }
+}
+
+impl BenchSettings {
+    /// Overrides settings with the `--bench-time`, `--min-samples`, `--max-samples` and `--warmup` arguments.
+    pub fn with_args(mut self, args: &mut pico_args::Arguments) -> Result<Self, pico_args::Error> {
+        let invalid = |cause: String| pico_args::Error::ArgumentParsingFailed { cause };
+
+        if let Some(time) = args.opt_value_from_fn("--bench-time", parse_duration)? {
+            self.time = time;
+        }
+        if let Some(min_samples) = args.opt_value_from_fn("--min-samples", parse_samples)? {
+            self.min_samples = min_samples;
+        }
+        if let Some(max_samples) = args.opt_value_from_fn("--max-samples", parse_samples)? {
+            self.max_samples = max_samples;
+        }
+        if let Some(warmup) = args.opt_value_from_fn("--warmup", parse_duration)? {
+            self.warmup = warmup;
+        }
+
+        self.validate().map_err(invalid)?;
+        Ok(self)
+    }
+
+    /// Formats the settings as arguments that [`BenchSettings::with_args`] understands.
+    #[must_use]
+    pub fn to_args(&self) -> Vec<String> {
+        vec![
+            "--bench-time".into(),
+            format!("{}ns", self.time.as_nanos()),
+            "--min-samples".into(),
+            self.min_samples.to_string(),
+            "--max-samples".into(),
+            self.max_samples.to_string(),
+            "--warmup".into(),
+            format!("{}ns", self.warmup.as_nanos()),
+        ]
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.min_samples > self.max_samples {
+            return Err(format!(
+                "the minimum number of samples ({}) exceeds the maximum ({})",
+                self.min_samples, self.max_samples
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Whether output is colored, resolved when the configuration is loaded.
+static COLOR: OnceLock<bool> = OnceLock::new();
+
+/// Returns the project configuration. The configuration is loaded once per process.
+///
+/// Invalid configuration files are reported and terminate the process.
+/// Binaries load it at startup, so that this does not happen while output is formatted.
+pub fn get() -> &'static Config {
+    static CONFIG: OnceLock<Config> = OnceLock::new();
+
+    CONFIG.get_or_init(|| {
+        let config = Config::load(Path::new(CONFIG_PATH)).unwrap_or_else(|e| {
+            eprintln!("Invalid configuration: {e}");
+            process::exit(1);
+        });
+        let _ = COLOR.set(config.color);
+        config
+    })
+}
+
+/// Whether output is colored. This never loads the configuration, so it is safe to call from `Display` impls.
+/// Before the configuration is loaded, only the `NO_COLOR` environment variable is respected.
+#[must_use]
+pub fn color() -> bool {
+    COLOR
+        .get()
+        .copied()
+        .unwrap_or_else(|| std::env::var_os("NO_COLOR").is_none())
+}
+
+/// Parses durations such as `1s`, `1.5s`, `500ms`, `250us` or `250µs`.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+
+    let (value, factor) = if let Some(value) = s.strip_suffix("ms") {
+        (value, 1e-3)
+    } else if let Some(value) = s.strip_suffix("µs").or_else(|| s.strip_suffix("us")) {
+        (value, 1e-6)
+    } else if let Some(value) = s.strip_suffix("ns") {
+        (value, 1e-9)
+    } else if let Some(value) = s.strip_suffix('s') {
+        (value, 1.0)
+    } else {
+        return Err(format!(
+            "expecting a duration with a unit, e.g. `1s` or `500ms`, got `{s}`"
+        ));
+    };
+
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite() && *v >= 0.0)
+        .map(|v| Duration::from_secs_f64(v * factor))
+        .ok_or_else(|| format!("invalid duration `{s}`"))
+}
+
+fn parse_samples(s: &str) -> Result<u128, String> {
+    match s.trim().parse::<u128>() {
+        Ok(samples) if samples > 0 => Ok(samples),
+        _ => Err(format!("expecting a positive number of samples, got `{s}`")),
+    }
+}
+
+fn positive_samples(samples: u64) -> Result<u128, String> {
+    if samples == 0 {
+        return Err("expecting a positive number of samples, got `0`".into());
+    }
+    Ok(samples.into())
+}
+
+/// Validates a value of the configuration file. Invalid values are reported with their key and line.
+fn check<T, U>(
+    s: &str,
+    key: &str,
+    value: &Spanned<T>,
+    validate: impl FnOnce(&T) -> Result<U, String>,
+) -> Result<U, Error> {
+    validate(value.get_ref()).map_err(|message| Error::InvalidValue {
+        key: key.into(),
+        line: line_of(s, value.span().start),
+        message,
+    })
+}
+
+/// Returns the 1-based line of a byte offset into `s`.
+fn line_of(s: &str, offset: usize) -> usize {
+    s[..offset].matches('\n').count() + 1
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Syntax {
+        line: usize,
+        message: String,
+    },
+    InvalidValue {
+        key: String,
+        line: usize,
+        message: String,
+    },
+    IO(io::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Syntax { line, message } => write!(f, "{CONFIG_PATH}:{line}: {message}"),
+            Error::InvalidValue { key, line, message } => {
+                write!(f, "{CONFIG_PATH}:{line}: `{key}`: {message}")
+            }
+            Error::IO(e) => write!(f, "could not read {CONFIG_PATH}: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{path::PathBuf, time::Duration};
+
+    use super::{parse_duration, BenchSettings, Config, Error};
+    use crate::template::{
+        input::TrailingNewline,
+        readme_benchmarks::{Column, Sort},
+    };
+
+    #[test]
+    fn parses_full_config() {
+        let config = Config::parse(
+            r#"
+            # comment
+            year = 2023
+            data_dir = "puzzle_data" # trailing comment
+            template = "my_template.txt"
+            color = false
+
+            [readme]
+            path = "docs/README.md"
+            marker = "<!-- #bench -->"
+            columns = ["day", "total", "share"]
+            sort = "cost"
+
+            [bench]
+            time = "500ms"
+            min_samples = 5
+            max_samples = 50
+            warmup = "1s"
+
+            [input]
+            normalize_line_endings = true
+            trailing_newline = "single"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.year, Some(2023));
+        assert_eq!(config.data_dir, PathBuf::from("puzzle_data"));
+        assert_eq!(config.template, Some(PathBuf::from("my_template.txt")));
+        assert!(!config.color);
+        assert_eq!(config.readme_path, PathBuf::from("docs/README.md"));
+        assert_eq!(config.readme_marker, "<!-- #bench -->");
+        assert_eq!(
+            config.readme_columns,
+            vec![Column::Day, Column::Total, Column::Share]
+        );
+        assert_eq!(config.readme_sort, Sort::Cost);
+        assert_eq!(config.bench.time, Duration::from_millis(500));
+        assert_eq!(config.bench.min_samples, 5);
+        assert_eq!(config.bench.max_samples, 50);
+        assert_eq!(config.bench.warmup, Duration::from_secs(1));
+        assert!(config.input.line_endings);
+        assert!(!config.input.strip_bom);
+        assert_eq!(config.input.trailing_newline, TrailingNewline::Single);
+    }
+
+    #[test]
+    fn empty_config_is_default() {
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn reports_offending_key() {
+        let err = Config::parse("year = 2023\n[bench]\ntime = \"5\"").unwrap_err();
+        match err {
+            Error::InvalidValue { key, line, .. } => {
+                assert_eq!(key, "bench.time");
+                assert_eq!(line, 3);
+            }
+            _ => panic!("unexpected error: {err}"),
+        }
+    }
+
+    #[test]
+    fn reports_invalid_types_with_line() {
+        let err = Config::parse("year = 2023\n[bench]\ntime = 5").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "aoc.toml:3: invalid type: integer `5`, expected a string"
+        );
+    }
+
+    #[test]
+    fn reports_conflicting_sample_bounds() {
+        let err = Config::parse("[bench]\nmax_samples = 5\nmin_samples = 10").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "aoc.toml:3: `bench.min_samples`: the minimum number of samples (10) exceeds the maximum (5)"
+        );
+    }
+
+    #[test]
+    fn overrides_bench_settings_with_args() {
+        let mut args = pico_args::Arguments::from_vec(
+            ["--bench-time", "2s", "--max-samples", "20"]
+                .iter()
+                .map(Into::into)
+                .collect(),
+        );
+        let settings = BenchSettings::default().with_args(&mut args).unwrap();
+        assert_eq!(settings.time, Duration::from_secs(2));
+        assert_eq!(settings.max_samples, 20);
+        assert_eq!(settings.min_samples, 10);
+
+        let mut args =
+            pico_args::Arguments::from_vec(settings.to_args().iter().map(Into::into).collect());
+        assert_eq!(
+            BenchSettings::default().with_args(&mut args).unwrap(),
+            settings
+        );
+    }
+
+    #[test]
+    fn reports_unknown_columns() {
+        let err = Config::parse("[readme]\ncolumns = [\"day\", \"part_3\"]").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("aoc.toml:2: `readme.columns`: unknown column `part_3`"));
+    }
+
+    #[test]
+    fn reports_unknown_key() {
+        let err = Config::parse("yaer = 2023").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("aoc.toml:1: unknown field `yaer`"));
+
+        let err = Config::parse("[bench]\nsamples = 10").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("aoc.toml:2: unknown field `samples`"));
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("1s"), Ok(Duration::from_secs(1)));
+        assert_eq!(parse_duration("1.5s"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("250us"), Ok(Duration::from_micros(250)));
+        assert!(parse_duration("10").is_err());
+        assert!(parse_duration("-1s").is_err());
+    }
+}
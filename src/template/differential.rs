@@ -0,0 +1,330 @@
+/// Module that checks two implementations of a part against each other on generated inputs.
+///
+/// This is meant for optimizing a solution: keep the straightforward version as the reference and
+/// compare the optimized one with it on many random inputs. If they disagree, the input is shrunk
+/// by removing lines, tokens and characters and by making numbers smaller, and the minimal counterexample is reported.
+///
+/// ```ignore
+/// use advent_of_code::template::{differential, rng::Rng};
+///
+/// fn generate(rng: &mut Rng) -> String {
+///     (0..rng.range(1, 10)).map(|_| rng.range(0, 100).to_string() + "\n").collect()
+/// }
+///
+/// #[test]
+/// fn optimized_part_one_matches() {
+///     differential::assert_equivalent(part_one_naive, part_one, generate);
+/// }
+/// ```
+use std::{
+    cell::Cell,
+    fmt::{Debug, Display},
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+use crate::template::rng::{Rng, SEED};
+
+/// Settings of a differential test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settings {
+    /// Number of inputs that are generated.
+    pub cases: usize,
+    /// Seed of the random number generator, fixed so that failures can be reproduced.
+    pub seed: u64,
+    /// Upper bound for the number of inputs tried while shrinking.
+    pub max_shrinks: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            cases: 256,
+            seed: SEED,
+            max_shrinks: 10_000,
+        }
+    }
+}
+
+/// An input that the implementations disagree on, along with both of their results.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counterexample {
+    pub input: String,
+    /// Result of the reference implementation, formatted with `{:?}`.
+    pub expected: String,
+    /// Result of the other implementation, formatted with `{:?}`.
+    pub actual: String,
+    /// Index of the generated input that failed first.
+    pub case: usize,
+    pub seed: u64,
+    /// Number of smaller inputs that still failed.
+    pub shrinks: usize,
+}
+
+impl Display for Counterexample {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "implementations disagree on case {} (seed {:#x}), shrunk {} times to:",
+            self.case, self.seed, self.shrinks
+        )?;
+        writeln!(f, "--- input ---")?;
+        writeln!(f, "{}", self.input.trim_end_matches('\n'))?;
+        writeln!(f, "--- expected ---")?;
+        writeln!(f, "{}", self.expected)?;
+        writeln!(f, "--- actual ---")?;
+        write!(f, "{}", self.actual)
+    }
+}
+
+/// Runs both implementations on generated inputs with the default [`Settings`].
+/// Returns the minimal counterexample if they disagree on any of them.
+pub fn check<R: PartialEq + Debug>(
+    reference: impl Fn(&str) -> R,
+    candidate: impl Fn(&str) -> R,
+    generate: impl Fn(&mut Rng) -> String,
+) -> Result<(), Counterexample> {
+    check_with(Settings::default(), reference, candidate, generate)
+}
+
+/// Runs both implementations on generated inputs. Panics count as a result, so an implementation
+/// that panics where the other does not is a counterexample as well.
+pub fn check_with<R: PartialEq + Debug>(
+    settings: Settings,
+    reference: impl Fn(&str) -> R,
+    candidate: impl Fn(&str) -> R,
+    generate: impl Fn(&mut Rng) -> String,
+) -> Result<(), Counterexample> {
+    let disagree = |input: &str| {
+        let expected = run(&reference, input);
+        let actual = run(&candidate, input);
+        (expected != actual).then_some((expected, actual))
+    };
+
+    let mut rng = Rng::new(settings.seed);
+
+    for case in 0..settings.cases {
+        let input = generate(&mut rng);
+        if disagree(&input).is_none() {
+            continue;
+        }
+
+        let (input, shrinks) = shrink(input, settings.max_shrinks, |input| {
+            disagree(input).is_some()
+        });
+        let (expected, actual) = disagree(&input).unwrap_or_default();
+
+        return Err(Counterexample {
+            input,
+            expected,
+            actual,
+            case,
+            seed: settings.seed,
+            shrinks,
+        });
+    }
+
+    Ok(())
+}
+
+/// Like [`check`], but prints the counterexample and panics, for use in tests.
+pub fn assert_equivalent<R: PartialEq + Debug>(
+    reference: impl Fn(&str) -> R,
+    candidate: impl Fn(&str) -> R,
+    generate: impl Fn(&mut Rng) -> String,
+) {
+    if let Err(counterexample) = check(reference, candidate, generate) {
+        panic!("{counterexample}");
+    }
+}
+
+thread_local! {
+    /// Whether panics on this thread are caught by the harness, which reports them itself.
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs an implementation, formatting its result or the message it panicked with.
+fn run<R: Debug>(func: impl Fn(&str) -> R, input: &str) -> String {
+    // the hook is shared by all threads, e.g. concurrently running tests, so it is only silenced per thread.
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.with(Cell::get) {
+                default_hook(info);
+            }
+        }));
+    });
+
+    QUIET.with(|quiet| quiet.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| func(input)));
+    QUIET.with(|quiet| quiet.set(false));
+
+    match result {
+        Ok(result) => format!("{result:?}"),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("Box<dyn Any>");
+            format!("panicked: {message}")
+        }
+    }
+}
+
+/// Shrinks a failing input until no smaller variant fails, or `max_shrinks` variants were tried.
+/// Returns the smallest failing input and the number of times it was shrunk.
+fn shrink(mut input: String, max_shrinks: usize, fails: impl Fn(&str) -> bool) -> (String, usize) {
+    let mut tries = 0;
+    let mut shrinks = 0;
+
+    loop {
+        let smaller = candidates(&input).into_iter().find(|candidate| {
+            tries += 1;
+            tries <= max_shrinks && fails(candidate)
+        });
+
+        match smaller {
+            Some(smaller) => {
+                input = smaller;
+                shrinks += 1;
+            }
+            None => return (input, shrinks),
+        }
+    }
+}
+
+/// Smaller variants of the input, the most aggressive ones first:
+/// without chunks of lines, without single tokens, with smaller numbers and without single characters.
+fn candidates(input: &str) -> Vec<String> {
+    let lines: Vec<&str> = input.lines().collect();
+    let trailing_newline = if input.ends_with('\n') { "\n" } else { "" };
+    let join = |lines: Vec<&str>| lines.join("\n") + trailing_newline;
+
+    let mut candidates = vec![];
+
+    let mut chunk = lines.len() / 2;
+    while chunk > 0 {
+        for start in (0..lines.len()).step_by(chunk) {
+            let mut remaining = lines.clone();
+            remaining.drain(start..(start + chunk).min(lines.len()));
+            candidates.push(join(remaining));
+        }
+        chunk /= 2;
+    }
+
+    for (i, line) in lines.iter().enumerate() {
+        let tokens: Vec<&str> = line.split(' ').collect();
+        for j in (0..tokens.len()).filter(|_| tokens.len() > 1) {
+            let mut remaining = tokens.clone();
+            remaining.remove(j);
+            let mut shrunk = lines.clone();
+            let line = remaining.join(" ");
+            shrunk[i] = &line;
+            candidates.push(join(shrunk));
+        }
+    }
+
+    for (start, end) in numbers(input) {
+        let Ok(number) = input[start..end].parse::<u64>() else {
+            continue;
+        };
+        for smaller in [0, 1, number / 2, number.saturating_sub(1)] {
+            if smaller < number {
+                candidates.push(format!("{}{smaller}{}", &input[..start], &input[end..]));
+            }
+        }
+    }
+
+    for (i, c) in input.char_indices() {
+        candidates.push(format!("{}{}", &input[..i], &input[i + c.len_utf8()..]));
+    }
+
+    // only strictly smaller inputs, which guarantees that shrinking terminates.
+    candidates.retain(|candidate| {
+        candidate.len() < input.len()
+            || (candidate.len() == input.len() && candidate.as_str() < input)
+    });
+    candidates.dedup();
+    candidates
+}
+
+/// Byte ranges of the numbers in the input.
+fn numbers(input: &str) -> Vec<(usize, usize)> {
+    let mut ranges = vec![];
+    let mut start = None;
+
+    for (i, c) in input.char_indices().chain([(input.len(), ' ')]) {
+        match (c.is_ascii_digit(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                ranges.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    ranges
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{check, check_with, Settings};
+    use crate::template::rng::Rng;
+
+    fn sum(input: &str) -> u64 {
+        input.lines().filter_map(|l| l.parse::<u64>().ok()).sum()
+    }
+
+    /// Mishandles numbers of 50 and above.
+    fn buggy_sum(input: &str) -> u64 {
+        input
+            .lines()
+            .filter_map(|l| l.parse::<u64>().ok())
+            .map(|n| n.min(49))
+            .sum()
+    }
+
+    fn generate(rng: &mut Rng) -> String {
+        (0..rng.range(1, 20))
+            .map(|_| format!("{}\n", rng.range(0, 1000)))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_equivalent_implementations() {
+        let sum_fold = |input: &str| {
+            input
+                .lines()
+                .fold(0, |acc, l| acc + l.parse::<u64>().unwrap_or(0))
+        };
+        assert_eq!(check(sum, sum_fold, generate), Ok(()));
+    }
+
+    #[test]
+    fn shrinks_counterexamples() {
+        let counterexample = check(sum, buggy_sum, generate).unwrap_err();
+        assert_eq!(counterexample.input, "50");
+        assert_eq!(counterexample.expected, "50");
+        assert_eq!(counterexample.actual, "49");
+        assert!(counterexample.to_string().contains("--- input ---\n50\n"));
+    }
+
+    #[test]
+    fn reports_panics() {
+        let panicking = |input: &str| -> u64 {
+            assert!(!input.contains('7'), "unexpected seven");
+            sum(input)
+        };
+        let settings = Settings {
+            seed: 1,
+            ..Settings::default()
+        };
+
+        let counterexample = check_with(settings, sum, panicking, generate).unwrap_err();
+        assert_eq!(counterexample.input, "7");
+        assert_eq!(counterexample.actual, "panicked: unexpected seven");
+    }
+}
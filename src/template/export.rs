@@ -0,0 +1,727 @@
+/// Module that exports benchmark timings to CSV or JSON and imports them back.
+/// Both formats hold one record per day and part, along with metadata about the environment the timings were taken in.
+use std::{
+    collections::BTreeMap, fmt::Write, fs, io, path::Path, process::Command, str::FromStr,
+    time::Duration,
+};
+
+use serde_json::{json, Value};
+
+use crate::template::{
+    config::{BenchSettings, Config},
+    readme_benchmarks::{self, Failure, SourceHashes, Step, Timing, Timings},
+    Day,
+};
+
+const CSV_HEADER: &str = "day,part,nanos,samples,deviation_nanos,cold_nanos,formatted,profile,commit,rustc,bench_time_nanos,min_samples,max_samples,warmup_nanos,failure,panicked,source_hash";
+
+#[derive(Debug)]
+pub enum Error {
+    Parser(String),
+    IO(io::Error),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "unknown export format `{s}`, expecting csv or json"
+            )),
+        }
+    }
+}
+
+/// The environment the timings were taken in.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Metadata {
+    /// The cargo profile the solutions were built with.
+    pub profile: String,
+    pub commit: Option<String>,
+    pub rustc: Option<String>,
+    /// The benchmark parameters, if the timings were benchmarked.
+    pub bench: Option<BenchSettings>,
+    /// Hashes of the solutions' source files, to tell whether the timings are stale.
+    pub source_hashes: SourceHashes,
+}
+
+impl Metadata {
+    /// Collects metadata about the current environment. Commit and compiler version are left empty if `git` or `rustc` are unavailable.
+    #[must_use]
+    pub fn collect(is_release: bool, bench: Option<BenchSettings>, config: &Config) -> Self {
+        let output = |cmd: &str, args: &[&str]| {
+            Command::new(cmd)
+                .args(args)
+                .output()
+                .ok()
+                .filter(|o| o.status.success())
+                .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+
+        Self {
+            profile: if is_release { "release" } else { "dev" }.into(),
+            commit: output("git", &["rev-parse", "HEAD"]),
+            rustc: output("rustc", &["--version"]),
+            bench,
+            source_hashes: readme_benchmarks::source_hashes(config),
+        }
+    }
+}
+
+/// A single exported measurement, or the failure of a step that was not measured.
+#[derive(Clone, Debug, PartialEq)]
+struct Record {
+    day: Day,
+    /// `parse`, `1` or `2`.
+    part: String,
+    entry: Entry,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Entry {
+    Timing(Timing),
+    Failure { message: String, panicked: bool },
+}
+
+/// Writes the timings to `path`.
+pub fn export(
+    path: &Path,
+    format: Format,
+    timings: &[Timings],
+    metadata: &Metadata,
+) -> Result<(), Error> {
+    let records = to_records(timings);
+
+    let content = match format {
+        Format::Csv => format_csv(&records, metadata),
+        Format::Json => format_json(&records, metadata),
+    };
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// Reads timings that were written by [`export`]. The format is detected from the file's content.
+pub fn import(path: &Path) -> Result<(Vec<Timings>, Metadata), Error> {
+    let content = fs::read_to_string(path)?;
+
+    let (records, metadata) = if content.trim_start().starts_with('{') {
+        parse_json(&content)?
+    } else {
+        parse_csv(&content)?
+    };
+
+    Ok((from_records(records), metadata))
+}
+
+fn to_records(timings: &[Timings]) -> Vec<Record> {
+    timings
+        .iter()
+        .flat_map(|t| {
+            let measured = [("parse", &t.parse), ("1", &t.part_1), ("2", &t.part_2)]
+                .into_iter()
+                .filter_map(|(part, timing)| {
+                    timing.as_ref().map(|timing| Record {
+                        day: t.day,
+                        part: part.into(),
+                        entry: Entry::Timing(timing.clone()),
+                    })
+                });
+
+            let failed = t.failures.iter().map(|failure| Record {
+                day: t.day,
+                part: match failure.step {
+                    Step::Parse => "parse".into(),
+                    Step::Part(part) => part.to_string(),
+                },
+                entry: Entry::Failure {
+                    message: failure.message.clone(),
+                    panicked: failure.panicked,
+                },
+            });
+
+            measured.chain(failed)
+        })
+        .collect()
+}
+
+fn from_records(records: Vec<Record>) -> Vec<Timings> {
+    let mut timings: BTreeMap<Day, Timings> = BTreeMap::new();
+
+    for record in records {
+        let entry = timings.entry(record.day).or_insert_with(|| Timings {
+            day: record.day,
+            parse: None,
+            part_1: None,
+            part_2: None,
+            total_nanos: 0_f64,
+            heap: None,
+            failures: vec![],
+        });
+
+        let step = match record.part.as_str() {
+            "parse" => Step::Parse,
+            "1" => Step::Part(1),
+            _ => Step::Part(2),
+        };
+
+        match record.entry {
+            Entry::Failure { message, panicked } => entry.failures.push(Failure {
+                step,
+                message,
+                panicked,
+            }),
+            Entry::Timing(timing) => {
+                entry.total_nanos += timing.nanos;
+                match step {
+                    Step::Parse => entry.parse = Some(timing),
+                    Step::Part(1) => entry.part_1 = Some(timing),
+                    Step::Part(_) => entry.part_2 = Some(timing),
+                }
+            }
+        }
+    }
+
+    timings.into_values().collect()
+}
+
+fn parse_record(day: &str, part: &str, entry: Entry) -> Result<Record, String> {
+    if !matches!(part, "parse" | "1" | "2") {
+        return Err(format!("unknown part `{part}`"));
+    }
+
+    Ok(Record {
+        day: day.parse().map_err(|_| format!("invalid day `{day}`"))?,
+        part: part.into(),
+        entry,
+    })
+}
+
+fn parse_timing(
+    nanos: &str,
+    samples: &str,
+    deviation: Option<&str>,
+    cold: Option<&str>,
+    formatted: &str,
+) -> Result<Timing, String> {
+    Ok(Timing {
+        formatted: formatted.into(),
+        nanos: nanos
+            .parse()
+            .map_err(|_| format!("invalid nanos `{nanos}`"))?,
+        samples: samples
+            .parse()
+            .map_err(|_| format!("invalid samples `{samples}`"))?,
+        deviation_nanos: deviation
+            .map(|d| d.parse().map_err(|_| format!("invalid deviation `{d}`")))
+            .transpose()?,
+        cold_nanos: cold
+            .map(|c| c.parse().map_err(|_| format!("invalid cold run `{c}`")))
+            .transpose()?,
+    })
+}
+
+fn parse_panicked(panicked: &str) -> Result<bool, String> {
+    panicked
+        .parse()
+        .map_err(|_| format!("invalid panicked flag `{panicked}`"))
+}
+
+fn parse_source_hash(hash: &str) -> Result<u64, String> {
+    u64::from_str_radix(hash, 16).map_err(|_| format!("invalid source hash `{hash}`"))
+}
+
+fn format_source_hash(metadata: &Metadata, day: Day) -> Option<String> {
+    metadata
+        .source_hashes
+        .get(&day)
+        .map(|hash| format!("{hash:016x}"))
+}
+
+fn parse_bench(
+    time_nanos: &str,
+    min_samples: &str,
+    max_samples: &str,
+    warmup_nanos: &str,
+) -> Result<BenchSettings, String> {
+    let nanos = |s: &str| {
+        s.parse()
+            .map(Duration::from_nanos)
+            .map_err(|_| format!("invalid duration `{s}`"))
+    };
+    let samples = |s: &str| s.parse().map_err(|_| format!("invalid samples `{s}`"));
+
+    Ok(BenchSettings {
+        time: nanos(time_nanos)?,
+        min_samples: samples(min_samples)?,
+        max_samples: samples(max_samples)?,
+        warmup: nanos(warmup_nanos)?,
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn format_csv(records: &[Record], metadata: &Metadata) -> String {
+    let mut s = format!("{CSV_HEADER}\n");
+
+    let bench = metadata.bench.map_or(vec![String::new(); 4], |b| {
+        vec![
+            b.time.as_nanos().to_string(),
+            b.min_samples.to_string(),
+            b.max_samples.to_string(),
+            b.warmup.as_nanos().to_string(),
+        ]
+    });
+
+    for record in records {
+        // failures have no timing, timings have no failure.
+        let (timing, failure) = match &record.entry {
+            Entry::Timing(timing) => (
+                [
+                    timing.nanos.to_string(),
+                    timing.samples.to_string(),
+                    timing
+                        .deviation_nanos
+                        .map(|d| d.to_string())
+                        .unwrap_or_default(),
+                    timing.cold_nanos.map(|c| c.to_string()).unwrap_or_default(),
+                    timing.formatted.clone(),
+                ],
+                [String::new(), String::new()],
+            ),
+            Entry::Failure { message, panicked } => {
+                (Default::default(), [message.clone(), panicked.to_string()])
+            }
+        };
+
+        let source_hash = format_source_hash(metadata, record.day).unwrap_or_default();
+        let metadata = [
+            metadata.profile.clone(),
+            metadata.commit.clone().unwrap_or_default(),
+            metadata.rustc.clone().unwrap_or_default(),
+        ];
+
+        let fields: Vec<String> = [record.day.to_string(), record.part.clone()]
+            .iter()
+            .chain(&timing)
+            .chain(&metadata)
+            .chain(&bench)
+            .chain(&failure)
+            .chain([&source_hash])
+            .map(|f| csv_escape(f))
+            .collect();
+        let _ = writeln!(s, "{}", fields.join(","));
+    }
+
+    s
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Splits csv content into records, along with the line each record starts on.
+/// Quoted fields can contain commas, quotes and line breaks. Blank lines are skipped.
+fn csv_records(s: &str) -> Vec<(usize, Vec<String>)> {
+    let mut records = vec![];
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut line = 1;
+    let mut start = 1;
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+            '\n' if !quoted => {
+                records.push((start, std::mem::replace(&mut fields, vec![String::new()])));
+                line += 1;
+                start = line;
+            }
+            c => {
+                if c == '\n' {
+                    line += 1;
+                }
+                fields.last_mut().unwrap().push(c);
+            }
+        }
+    }
+    records.push((start, fields));
+
+    records.retain(|(_, fields)| !matches!(&fields[..], [field] if field.trim().is_empty()));
+    records
+}
+
+fn parse_csv(s: &str) -> Result<(Vec<Record>, Metadata), Error> {
+    let mut lines = csv_records(s).into_iter();
+
+    match lines.next() {
+        Some((_, header)) if header.join(",").trim() == CSV_HEADER => {}
+        _ => return Err(Error::Parser(format!("expected csv header `{CSV_HEADER}`"))),
+    }
+
+    let mut records = vec![];
+    let mut metadata = Metadata::default();
+    let mut source_hashes = SourceHashes::new();
+
+    for (line, fields) in lines {
+        let error = |message: String| Error::Parser(format!("line {line}: {message}"));
+
+        let [day, part, nanos, samples, deviation, cold, formatted, profile, commit, rustc, bench @ ..] =
+            &fields[..]
+        else {
+            return Err(error(format!("expected 17 fields, found {}", fields.len())));
+        };
+        let [bench_time, min_samples, max_samples, warmup, failure, panicked, source_hash] = bench
+        else {
+            return Err(error(format!("expected 17 fields, found {}", fields.len())));
+        };
+
+        let non_empty = |s: &String| Some(s.clone()).filter(|s| !s.is_empty());
+
+        let entry = if failure.is_empty() {
+            let (deviation, cold) = (non_empty(deviation), non_empty(cold));
+            Entry::Timing(
+                parse_timing(
+                    nanos,
+                    samples,
+                    deviation.as_deref(),
+                    cold.as_deref(),
+                    formatted,
+                )
+                .map_err(error)?,
+            )
+        } else {
+            Entry::Failure {
+                message: failure.clone(),
+                panicked: parse_panicked(panicked).map_err(error)?,
+            }
+        };
+        let record = parse_record(day, part, entry).map_err(error)?;
+        if !source_hash.is_empty() {
+            source_hashes.insert(record.day, parse_source_hash(source_hash).map_err(error)?);
+        }
+        records.push(record);
+
+        metadata = Metadata {
+            profile: profile.clone(),
+            commit: non_empty(commit),
+            rustc: non_empty(rustc),
+            bench: if bench_time.is_empty() {
+                None
+            } else {
+                Some(parse_bench(bench_time, min_samples, max_samples, warmup).map_err(error)?)
+            },
+            source_hashes: SourceHashes::new(),
+        };
+    }
+
+    metadata.source_hashes = source_hashes;
+    Ok((records, metadata))
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn format_json(records: &[Record], metadata: &Metadata) -> String {
+    let records: Vec<Value> = records
+        .iter()
+        .map(|record| match &record.entry {
+            Entry::Timing(timing) => json!({
+                "day": record.day.into_inner(),
+                "part": record.part,
+                "nanos": timing.nanos,
+                "samples": timing.samples,
+                "deviation_nanos": timing.deviation_nanos,
+                "cold_nanos": timing.cold_nanos,
+                "formatted": timing.formatted,
+                "source_hash": format_source_hash(metadata, record.day),
+            }),
+            Entry::Failure { message, panicked } => json!({
+                "day": record.day.into_inner(),
+                "part": record.part,
+                "failure": message,
+                "panicked": panicked,
+                "source_hash": format_source_hash(metadata, record.day),
+            }),
+        })
+        .collect();
+
+    let bench = metadata.bench.map(|b| {
+        json!({
+            "time_nanos": b.time.as_nanos(),
+            "min_samples": b.min_samples,
+            "max_samples": b.max_samples,
+            "warmup_nanos": b.warmup.as_nanos(),
+        })
+    });
+
+    let value = json!({
+        "profile": metadata.profile,
+        "commit": metadata.commit,
+        "rustc": metadata.rustc,
+        "bench": bench,
+        "records": records,
+    });
+
+    format!("{value:#}\n")
+}
+
+fn parse_json(s: &str) -> Result<(Vec<Record>, Metadata), Error> {
+    let value: Value = serde_json::from_str(s).map_err(|e| Error::Parser(e.to_string()))?;
+
+    let string = |name: &str| -> Result<Option<String>, String> {
+        match value.get(name) {
+            None => Err(format!("missing field `{name}`")),
+            Some(Value::Null) => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s.clone())),
+            Some(_) => Err(format!("field `{name}` is not a string")),
+        }
+    };
+
+    // files exported before the benchmark parameters were recorded have no `bench` field.
+    let bench = match value.get("bench") {
+        None | Some(Value::Null) => None,
+        Some(bench) => {
+            let number = |name: &str| match bench.get(name) {
+                Some(Value::Number(n)) => Ok(n.to_string()),
+                _ => Err(format!("bench: missing field `{name}`")),
+            };
+            Some(
+                parse_bench(
+                    &number("time_nanos").map_err(Error::Parser)?,
+                    &number("min_samples").map_err(Error::Parser)?,
+                    &number("max_samples").map_err(Error::Parser)?,
+                    &number("warmup_nanos").map_err(Error::Parser)?,
+                )
+                .map_err(Error::Parser)?,
+            )
+        }
+    };
+
+    let mut metadata = Metadata {
+        profile: string("profile")
+            .map_err(Error::Parser)?
+            .unwrap_or_default(),
+        commit: string("commit").map_err(Error::Parser)?,
+        rustc: string("rustc").map_err(Error::Parser)?,
+        bench,
+        source_hashes: SourceHashes::new(),
+    };
+
+    let values = match value.get("records") {
+        None => return Err(Error::Parser("missing field `records`".into())),
+        Some(Value::Array(values)) => values,
+        Some(_) => return Err(Error::Parser("field `records` is not an array".into())),
+    };
+
+    let records = values
+        .iter()
+        .enumerate()
+        .map(|(i, record)| {
+            let scalar = |name: &str| match record.get(name) {
+                Some(Value::Number(n)) => Ok(n.to_string()),
+                Some(Value::String(s)) => Ok(s.clone()),
+                Some(Value::Bool(b)) => Ok(b.to_string()),
+                _ => Err(format!("record {i}: missing field `{name}`")),
+            };
+            let error = |e: String| format!("record {i}: {e}");
+
+            let entry = if let Some(message) = record.get("failure").and_then(Value::as_str) {
+                Entry::Failure {
+                    message: message.into(),
+                    panicked: parse_panicked(&scalar("panicked")?).map_err(error)?,
+                }
+            } else {
+                // the deviation and cold run are optional, as they are unknown for single runs.
+                let deviation = scalar("deviation_nanos").ok();
+                let cold = scalar("cold_nanos").ok();
+
+                Entry::Timing(
+                    parse_timing(
+                        &scalar("nanos")?,
+                        &scalar("samples")?,
+                        deviation.as_deref(),
+                        cold.as_deref(),
+                        &scalar("formatted")?,
+                    )
+                    .map_err(error)?,
+                )
+            };
+
+            let parsed = parse_record(&scalar("day")?, &scalar("part")?, entry).map_err(error)?;
+
+            // files exported before source hashes were recorded have no `source_hash` field.
+            if let Some(hash) = record.get("source_hash").and_then(Value::as_str) {
+                let hash = parse_source_hash(hash).map_err(error)?;
+                metadata.source_hashes.insert(parsed.day, hash);
+            }
+
+            Ok(parsed)
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(Error::Parser)?;
+
+    Ok((records, metadata))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        format_csv, format_json, from_records, parse_csv, parse_json, to_records, Metadata,
+        CSV_HEADER,
+    };
+    use crate::{
+        day,
+        template::{
+            config::BenchSettings,
+            readme_benchmarks::{fixtures, Failure, SourceHashes, Step, Timing, Timings},
+        },
+    };
+
+    fn timing(formatted: &str, nanos: f64, samples: u128) -> Option<Timing> {
+        Some(Timing {
+            samples,
+            ..fixtures::timing(formatted, nanos)
+        })
+    }
+
+    fn get_mock_timings() -> Vec<Timings> {
+        vec![
+            Timings {
+                parse: timing("10ns", 10.0, 10000),
+                total_nanos: 1_200_084.13,
+                ..fixtures::timings(
+                    day!(1),
+                    timing("74.13ns", 74.13, 10000),
+                    timing("1.2ms", 1_200_000.0, 834),
+                )
+            },
+            Timings {
+                failures: vec![Failure {
+                    step: Step::Part(2),
+                    message: "invalid card: \"Card 1, 2\"\nexpected 10 numbers".into(),
+                    panicked: false,
+                }],
+                ..fixtures::timings(
+                    day!(3),
+                    Some(Timing {
+                        deviation_nanos: Some(12_345.5),
+                        cold_nanos: Some(3_100_000_000.0),
+                        ..fixtures::timing("2.5s", 2_500_000_000.0)
+                    }),
+                    None,
+                )
+            },
+            Timings {
+                failures: vec![Failure {
+                    step: Step::Parse,
+                    message: "empty input at src/bin/04.rs:9:5".into(),
+                    panicked: true,
+                }],
+                ..fixtures::timings(day!(4), None, None)
+            },
+        ]
+    }
+
+    fn get_mock_metadata() -> Metadata {
+        Metadata {
+            profile: "release".into(),
+            commit: Some("0123abc".into()),
+            rustc: Some("rustc 1.75.0 (82e1608df 2023-12-21), \"stable\"".into()),
+            bench: Some(BenchSettings::default()),
+            source_hashes: SourceHashes::from([(day!(1), 0xabc)]),
+        }
+    }
+
+    #[test]
+    fn creates_one_record_per_part() {
+        let records = to_records(&get_mock_timings());
+        let parts: Vec<_> = records
+            .iter()
+            .map(|r| format!("{}/{}", r.day, r.part))
+            .collect();
+        assert_eq!(
+            parts,
+            ["01/parse", "01/1", "01/2", "03/1", "03/2", "04/parse"]
+        );
+    }
+
+    #[test]
+    fn round_trips_csv() {
+        let records = to_records(&get_mock_timings());
+        let csv = format_csv(&records, &get_mock_metadata());
+
+        assert!(csv.contains("01,1,74.13,10000,,,74.13ns,release,0123abc,"));
+        assert!(csv.contains(",1000000000,10,10000,100000000,,,0000000000000abc\n"));
+        assert!(csv.contains("03,2,,,,,,release,"));
+        assert!(
+            csv.contains(",\"invalid card: \"\"Card 1, 2\"\"\nexpected 10 numbers\",false,\n04,")
+        );
+
+        let (parsed, metadata) = parse_csv(&csv).unwrap();
+        assert_eq!(metadata, get_mock_metadata());
+        assert_eq!(from_records(parsed), get_mock_timings());
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let records = to_records(&get_mock_timings());
+        let json = format_json(&records, &get_mock_metadata());
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value["records"][1],
+            serde_json::json!({ "day": 1, "part": "1", "nanos": 74.13, "samples": 10000, "deviation_nanos": null, "cold_nanos": null, "formatted": "74.13ns", "source_hash": "0000000000000abc" })
+        );
+        assert_eq!(
+            value["bench"],
+            serde_json::json!({ "time_nanos": 1_000_000_000, "min_samples": 10, "max_samples": 10000, "warmup_nanos": 100_000_000 })
+        );
+        assert_eq!(
+            value["records"][5],
+            serde_json::json!({ "day": 4, "part": "parse", "failure": "empty input at src/bin/04.rs:9:5", "panicked": true, "source_hash": null })
+        );
+
+        let (parsed, metadata) = parse_json(&json).unwrap();
+        assert_eq!(metadata, get_mock_metadata());
+        assert_eq!(from_records(parsed), get_mock_timings());
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        assert!(parse_csv("day,part\n01,1").is_err());
+        assert!(parse_csv(&format!("{CSV_HEADER}\n01,1,\"unterminated\n")).is_err());
+        assert!(parse_json(r#"{ "profile": "release", "records": [ { "day": 1 } ] }"#).is_err());
+        assert!(parse_json(r#"{ "records": [ "#).is_err());
+    }
+}
@@ -0,0 +1,244 @@
+/// Module that runs solutions on generated inputs, to find out how their runtime grows with the size of the input.
+///
+/// A day registers a generator with `solution!(<day>, generator = <function>)`. The generator receives a
+/// seeded [`Rng`] and a scale, and returns an input that grows linearly with the scale.
+/// `cargo gen <day> --scale <scale>` writes a generated input to `data/generated`, and
+/// `cargo solve <day> --generated <scale>` benchmarks the day on inputs of scale 1, 2, 4… up to the given one.
+use std::{env, io::Write, process};
+
+use crate::template::{
+    readme_benchmarks::{format_bytes, Step, Timing, Timings},
+    rng::{Rng, SEED},
+    table, Day, ANSI_BOLD, ANSI_RESET,
+};
+
+/// Generates an input of the given scale.
+pub type Generator = fn(&mut Rng, usize) -> String;
+
+/// Parses a scale, which needs to be a positive integer.
+pub fn parse_scale(s: &str) -> Result<usize, String> {
+    match s.parse::<usize>() {
+        Ok(scale) if scale > 0 => Ok(scale),
+        _ => Err(format!("invalid scale `{s}`, expecting a positive integer")),
+    }
+}
+
+/// Generates the input of a day at the given scale.
+#[must_use]
+pub fn generate(generator: Generator, scale: usize) -> String {
+    generator(&mut Rng::new(SEED), scale)
+}
+
+/// Reads the input of a solution binary, or generates it if the binary was invoked with a scale:
+///  1. with `--generate <scale>`, the generated input is printed and the process exits.
+///  2. with `--generated <scale>`, the solution runs on the generated input.
+///
+/// Exits with an error if a scale is passed but the day has no generator.
+#[must_use]
+pub fn read_input(day: Day, generator: Option<Generator>) -> String {
+    let print = scale_arg("--generate");
+    let solve = scale_arg("--generated");
+
+    let Some(scale) = print.or(solve) else {
+        return crate::template::read_input(day);
+    };
+
+    let Some(generator) = generator else {
+        eprintln!(
+            "Day {day} has no input generator. Register one with `solution!({}, generator = <function>)`.",
+            day.into_inner()
+        );
+        process::exit(1);
+    };
+
+    let input = generate(generator, scale);
+
+    if print.is_some() {
+        let _ = std::io::stdout().write_all(input.as_bytes());
+        process::exit(0);
+    }
+
+    println!("Generated input: {} bytes at scale {scale}.", input.len());
+    input
+}
+
+fn scale_arg(name: &str) -> Option<usize> {
+    let args: Vec<String> = env::args().collect();
+    let index = args.iter().position(|x| x == name)?;
+
+    match args.get(index + 1).map(|s| parse_scale(s)) {
+        Some(Ok(scale)) => Some(scale),
+        Some(Err(e)) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+        None => {
+            eprintln!("`{name}` expects a scale.");
+            process::exit(1);
+        }
+    }
+}
+
+/// Scales to benchmark: powers of two up to `max`, followed by `max` itself.
+#[must_use]
+pub fn scales(max: usize) -> Vec<usize> {
+    let mut scales: Vec<usize> = (0..)
+        .map(|exp| 1 << exp)
+        .take_while(|&scale| scale < max)
+        .collect();
+    scales.push(max.max(1));
+    scales
+}
+
+/// Benchmark of a day on a generated input.
+pub struct GrowthRun {
+    pub scale: usize,
+    /// Size of the generated input in bytes.
+    pub bytes: u64,
+    pub timings: Timings,
+}
+
+/// Parses the size of the input that the runner prints with `--generated`.
+#[must_use]
+pub fn parse_input_size(output: &[String]) -> Option<u64> {
+    output.iter().find_map(|l| {
+        l.strip_prefix("Generated input: ")?
+            .split(' ')
+            .next()?
+            .parse()
+            .ok()
+    })
+}
+
+/// Formats one line per run with each part's time and its growth compared to the previous run,
+/// followed by the estimated exponent of each part's growth from the first to the last run.
+#[must_use]
+pub fn format_table(runs: &[GrowthRun]) -> Vec<String> {
+    let (Some(first), Some(last)) = (runs.first(), runs.last()) else {
+        return vec![];
+    };
+
+    let steps: Vec<Step> = Step::ALL
+        .into_iter()
+        .filter(|&step| runs.iter().any(|run| run.timings.step(step).is_some()))
+        .collect();
+
+    let mut rows = vec![["Scale", "Size"]
+        .into_iter()
+        .map(String::from)
+        .chain(steps.iter().map(Step::to_string))
+        .collect::<Vec<_>>()];
+
+    for (index, run) in runs.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|i| &runs[i]);
+        let mut row = vec![run.scale.to_string(), format_bytes(run.bytes)];
+
+        for &step in &steps {
+            let timing = run.timings.step(step);
+            let previous = previous.and_then(|p| p.timings.step(step));
+
+            row.push(match (timing, previous) {
+                (Some(timing), Some(previous)) if previous.nanos > 0.0 => {
+                    format!(
+                        "{} (×{:.2})",
+                        timing.formatted,
+                        timing.nanos / previous.nanos
+                    )
+                }
+                (Some(timing), _) => timing.formatted.clone(),
+                (None, _) => "-".into(),
+            });
+        }
+
+        rows.push(row);
+    }
+
+    if runs.len() > 1 {
+        let mut row = vec!["Growth".to_string(), String::new()];
+
+        for &step in &steps {
+            row.push(
+                match (first.timings.step(step), last.timings.step(step)) {
+                    (Some(a), Some(b)) => exponent(first.scale, a, last.scale, b),
+                    _ => None,
+                }
+                .map_or_else(|| "-".into(), |k| format!("~n^{k:.2}")),
+            );
+        }
+
+        rows.push(row);
+    }
+
+    table::format(&rows)
+}
+
+/// Estimates `k` in `time ~ n^k` from two measurements, where `n` is the scale.
+/// Generated inputs grow linearly with the scale, but their size in bytes may be dominated by a fixed part.
+fn exponent(scale_a: usize, a: &Timing, scale_b: usize, b: &Timing) -> Option<f64> {
+    #[allow(clippy::cast_precision_loss)]
+    let scale_ratio = scale_b as f64 / scale_a as f64;
+    let time_ratio = b.nanos / a.nanos;
+
+    (scale_ratio > 1.0 && time_ratio.is_finite() && time_ratio > 0.0)
+        .then(|| time_ratio.ln() / scale_ratio.ln())
+}
+
+pub fn print(day: Day, runs: &[GrowthRun]) {
+    println!(
+        "\n{ANSI_BOLD}Growth of day {day}{ANSI_RESET} (compared to the previous scale, n is the scale):"
+    );
+
+    for line in format_table(runs) {
+        println!("{line}");
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_table, parse_input_size, parse_scale, scales, GrowthRun};
+    use crate::{
+        day,
+        template::readme_benchmarks::fixtures::{timing, timings},
+    };
+
+    fn run(scale: usize, part_1: f64) -> GrowthRun {
+        GrowthRun {
+            scale,
+            bytes: scale as u64 * 1000,
+            timings: timings(
+                day!(4),
+                Some(timing(&format!("{part_1}ms"), part_1 * 1_000_000.0)),
+                None,
+            ),
+        }
+    }
+
+    #[test]
+    fn parses_scales() {
+        assert_eq!(parse_scale("8"), Ok(8));
+        assert!(parse_scale("0").is_err());
+        assert_eq!(scales(1), [1]);
+        assert_eq!(scales(12), [1, 2, 4, 8, 12]);
+    }
+
+    #[test]
+    fn parses_input_size() {
+        let output = ["Generated input: 1234 bytes at scale 2.".to_string()];
+        assert_eq!(parse_input_size(&output), Some(1234));
+    }
+
+    #[test]
+    fn reports_growth() {
+        let table = format_table(&[run(1, 1.0), run(2, 4.0), run(4, 16.0)]);
+        assert_eq!(
+            table,
+            [
+                "Scale   Size     Part 1",
+                "1       1000 B   1ms",
+                "2       2.0 KiB  4ms (×4.00)",
+                "4       3.9 KiB  16ms (×4.00)",
+                "Growth           ~n^2.00",
+            ]
+        );
+    }
+}
@@ -0,0 +1,228 @@
+/// Module that loads puzzle inputs and examples.
+///
+/// Loading fails with an [`Error`] that names the file and suggests how to fix it.
+/// Inputs and examples are normalized the same way, according to the `input` section of the configuration,
+/// so that e.g. a file saved with CRLF line endings does not change results.
+use std::{
+    fmt::Display,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::template::{config, Day};
+
+#[derive(Debug)]
+pub enum Error {
+    /// The file does not exist. The day is set for puzzle inputs, which can be downloaded.
+    Missing {
+        path: PathBuf,
+        day: Option<Day>,
+    },
+    /// The puzzle input is empty or only contains whitespace.
+    Empty {
+        path: PathBuf,
+        day: Day,
+    },
+    IO {
+        path: PathBuf,
+        error: io::Error,
+    },
+    Stdin(io::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Missing {
+                path,
+                day: Some(day),
+            } => write!(
+                f,
+                "{} does not exist. Run `cargo download {day}` to download the input.",
+                path.display()
+            ),
+            Error::Missing { path, day: None } => write!(f, "{} does not exist.", path.display()),
+            Error::Empty { path, day } => write!(
+                f,
+                "{} is empty. Run `cargo download {day}` to download the input.",
+                path.display()
+            ),
+            Error::IO { path, error } => write!(f, "could not read {}: {error}", path.display()),
+            Error::Stdin(e) => write!(f, "could not read the input from stdin: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// How the trailing newline of a file is treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrailingNewline {
+    /// Leave the end of the file as is.
+    Keep,
+    /// Remove all trailing line breaks.
+    Strip,
+    /// End the file with exactly one line break.
+    Single,
+}
+
+impl FromStr for TrailingNewline {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "keep" => Ok(Self::Keep),
+            "strip" => Ok(Self::Strip),
+            "single" => Ok(Self::Single),
+            _ => Err(format!(
+                "unknown trailing newline policy `{s}`, expecting `keep`, `strip` or `single`"
+            )),
+        }
+    }
+}
+
+/// Normalizations applied to inputs and examples. All of them are disabled by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Normalization {
+    /// Convert CRLF line endings to LF.
+    pub line_endings: bool,
+    /// Remove a leading UTF-8 byte order mark.
+    pub strip_bom: bool,
+    pub trailing_newline: TrailingNewline,
+}
+
+impl Default for Normalization {
+    fn default() -> Self {
+        Self {
+            line_endings: false,
+            strip_bom: false,
+            trailing_newline: TrailingNewline::Keep,
+        }
+    }
+}
+
+impl Normalization {
+    #[must_use]
+    pub fn apply(&self, mut s: String) -> String {
+        if self.strip_bom && s.starts_with('\u{feff}') {
+            s.remove(0);
+        }
+
+        if self.line_endings && s.contains('\r') {
+            s = s.replace("\r\n", "\n");
+        }
+
+        match self.trailing_newline {
+            TrailingNewline::Keep => {}
+            TrailingNewline::Strip => s.truncate(s.trim_end_matches(['\r', '\n']).len()),
+            TrailingNewline::Single => {
+                s.truncate(s.trim_end_matches(['\r', '\n']).len());
+                s.push('\n');
+            }
+        }
+
+        s
+    }
+}
+
+/// Loads the puzzle input of a day, e.g. `data/inputs/01.txt`.
+pub fn load_input(day: Day) -> Result<String, Error> {
+    let path = config::get().input_path(day);
+    let input = read(&path, Some(day))?;
+
+    if input.trim().is_empty() {
+        return Err(Error::Empty { path, day });
+    }
+
+    Ok(input)
+}
+
+/// Loads a file from a folder of the data directory, e.g. `data/examples/01.txt`.
+/// A part suffix selects a file that only applies to one part, e.g. `01-2.txt`.
+pub fn load_file(folder: &str, day: Day, part: Option<u8>) -> Result<String, Error> {
+    let name = match part {
+        Some(part) => format!("{day}-{part}.txt"),
+        None => format!("{day}.txt"),
+    };
+
+    read(&config::get().data_dir.join(folder).join(name), None)
+}
+
+/// Loads a custom input, `-` reads it from stdin.
+pub fn load_custom(path: &str) -> Result<String, Error> {
+    if path != "-" {
+        return read(Path::new(path), None);
+    }
+
+    let mut input = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .map_err(Error::Stdin)?;
+
+    Ok(config::get().input.apply(input))
+}
+
+fn read(path: &Path, day: Option<Day>) -> Result<String, Error> {
+    match fs::read_to_string(path) {
+        Ok(s) => Ok(config::get().input.apply(s)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Err(Error::Missing {
+            path: path.to_path_buf(),
+            day,
+        }),
+        Err(error) => Err(Error::IO {
+            path: path.to_path_buf(),
+            error,
+        }),
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{Error, Normalization, TrailingNewline};
+    use crate::day;
+
+    fn normalize(s: &str, trailing_newline: TrailingNewline) -> String {
+        Normalization {
+            line_endings: true,
+            strip_bom: true,
+            trailing_newline,
+        }
+        .apply(s.to_string())
+    }
+
+    #[test]
+    fn normalizes_line_endings_and_bom() {
+        assert_eq!(
+            normalize("\u{feff}a\r\nb\r\n", TrailingNewline::Keep),
+            "a\nb\n"
+        );
+        assert_eq!(normalize("a\r\nb\r\n\r\n", TrailingNewline::Strip), "a\nb");
+        assert_eq!(normalize("a\nb", TrailingNewline::Single), "a\nb\n");
+        assert_eq!(
+            Normalization::default().apply("\u{feff}a\r\n".into()),
+            "\u{feff}a\r\n"
+        );
+    }
+
+    #[test]
+    fn suggests_downloading_inputs() {
+        let missing = Error::Missing {
+            path: PathBuf::from("data/inputs/05.txt"),
+            day: Some(day!(5)),
+        };
+        assert_eq!(
+            missing.to_string(),
+            "data/inputs/05.txt does not exist. Run `cargo download 05` to download the input."
+        );
+
+        let example = Error::Missing {
+            path: PathBuf::from("data/examples/05.txt"),
+            day: None,
+        };
+        assert_eq!(example.to_string(), "data/examples/05.txt does not exist.");
+    }
+}
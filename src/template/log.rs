@@ -0,0 +1,131 @@
+/// Module behind the [`debug!`](crate::debug) and [`trace!`](crate::trace) macros, which solutions use instead of `println!`.
+///
+/// Messages are printed to stderr, and only if the solution runs with `--verbose` (`-v`) for debug messages
+/// or `-vv` for trace messages as well. They are silenced while a part is benchmarked.
+/// Release builds compile the macros to nothing, unless the `debug-log` feature is enabled,
+/// which `cargo solve --release --verbose` does.
+use std::{
+    fmt::Arguments,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+};
+
+use crate::template::{ANSI_ITALIC, ANSI_RESET};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Debug = 1,
+    Trace = 2,
+}
+
+impl Level {
+    fn name(self) -> &'static str {
+        match self {
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+}
+
+static SILENCED: AtomicBool = AtomicBool::new(false);
+
+/// Reads the verbosity from `-vv`, or `-v` and `--verbose`. Returns 0 if neither is passed.
+pub fn parse_verbosity(args: &mut pico_args::Arguments) -> u8 {
+    if args.contains("-vv") {
+        2
+    } else {
+        u8::from(args.contains(["-v", "--verbose"]))
+    }
+}
+
+/// Formats the verbosity as arguments that [`parse_verbosity`] understands.
+#[must_use]
+pub fn verbosity_args(verbosity: u8) -> Vec<String> {
+    match verbosity {
+        0 => vec![],
+        1 => vec!["-v".into()],
+        _ => vec!["-vv".into()],
+    }
+}
+
+/// Verbosity the solution was started with.
+fn verbosity() -> u8 {
+    static VERBOSITY: OnceLock<u8> = OnceLock::new();
+    *VERBOSITY.get_or_init(|| parse_verbosity(&mut pico_args::Arguments::from_env()))
+}
+
+/// Whether messages of the level are printed right now.
+#[must_use]
+pub fn enabled(level: Level) -> bool {
+    !SILENCED.load(Ordering::Relaxed) && level as u8 <= verbosity()
+}
+
+/// Silences all messages, e.g. while a part is benchmarked.
+pub fn silence(silenced: bool) {
+    SILENCED.store(silenced, Ordering::Relaxed);
+}
+
+#[doc(hidden)]
+pub fn print(level: Level, args: Arguments) {
+    eprintln!("{ANSI_ITALIC}[{}]{ANSI_RESET} {args}", level.name());
+}
+
+/// Prints a message at the given level. Use [`debug!`](crate::debug) or [`trace!`](crate::trace) instead.
+///
+/// If logging is compiled out, the arguments are still type checked, so variables that are only logged
+/// do not cause warnings, but the optimizer removes the call.
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {
+        if cfg!(any(debug_assertions, feature = "debug-log"))
+            && $crate::template::log::enabled($level)
+        {
+            $crate::template::log::print($level, format_args!($($arg)+));
+        }
+    };
+}
+
+/// Prints a message like `println!` if the solution runs with `--verbose`.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::template::log::Level::Debug, $($arg)+)
+    };
+}
+
+/// Prints a message like `println!` if the solution runs with `-vv`.
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::template::log::Level::Trace, $($arg)+)
+    };
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_verbosity, verbosity_args};
+
+    fn verbosity(args: &[&str]) -> u8 {
+        parse_verbosity(&mut pico_args::Arguments::from_vec(
+            args.iter().map(Into::into).collect(),
+        ))
+    }
+
+    #[test]
+    fn parses_verbosity() {
+        assert_eq!(verbosity(&[]), 0);
+        assert_eq!(verbosity(&["--verbose"]), 1);
+        assert_eq!(verbosity(&["-v"]), 1);
+        assert_eq!(verbosity(&["-vv"]), 2);
+
+        for level in 0..=2 {
+            let args = verbosity_args(level);
+            assert_eq!(
+                verbosity(&args.iter().map(String::as_str).collect::<Vec<_>>()),
+                level
+            );
+        }
+    }
+}
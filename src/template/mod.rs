@@ -1,54 +1,140 @@
-use std::{env, fs};
+use std::{env, fmt::Display, process};
 
 pub mod aoc_cli;
+pub mod baseline;
+pub mod calendar;
+pub mod cli;
 pub mod commands;
+pub mod config;
 mod day;
+pub mod differential;
+pub mod export;
+pub mod generate;
+pub mod input;
+pub mod log;
+pub mod ocr;
+#[cfg(feature = "cpu-profile")]
+pub mod profiler;
 pub mod readme_benchmarks;
+pub mod report;
+pub mod rng;
 pub mod runner;
+pub mod scaling;
+pub mod solution;
+pub mod table;
+pub mod variants;
+pub mod visualize;
 
 pub use day::*;
+pub use solution::Solution;
 
-pub const ANSI_ITALIC: &str = "\x1b[3m";
-pub const ANSI_BOLD: &str = "\x1b[1m";
-pub const ANSI_RESET: &str = "\x1b[0m";
+/// An ANSI escape sequence. It displays as an empty string if colors are disabled in the [`config`].
+#[derive(Debug, Clone, Copy)]
+pub struct Ansi(&'static str);
 
-/// Helper function that reads a text file to a string.
+impl Ansi {
+    /// Returns the raw escape sequence, regardless of the color configuration.
+    #[must_use]
+    pub const fn code(self) -> &'static str {
+        self.0
+    }
+}
+
+impl Display for Ansi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if config::color() {
+            f.write_str(self.0)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+pub const ANSI_ITALIC: Ansi = Ansi("\x1b[3m");
+pub const ANSI_BOLD: Ansi = Ansi("\x1b[1m");
+pub const ANSI_RESET: Ansi = Ansi("\x1b[0m");
+pub const ANSI_RED: Ansi = Ansi("\x1b[31m");
+pub const ANSI_GREEN: Ansi = Ansi("\x1b[32m");
+pub const ANSI_YELLOW: Ansi = Ansi("\x1b[33m");
+pub const ANSI_CLEAR_SCREEN: Ansi = Ansi("\x1b[2J\x1b[1;1H");
+
+/// Helper function that reads a text file to a string. Panics if the file cannot be read.
+///
+/// Use [`input::load_file`] to handle the error instead.
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    input::load_file(folder, day, None).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// Reads the puzzle input for a day. Prints the error and exits if the input cannot be read.
+///
+/// Defaults to `inputs/{day}.txt` in the configured data directory. Passing `--input <path>` to the binary
+/// reads the given file instead, `--input -` reads the input from stdin.
+#[must_use]
+pub fn read_input(day: Day) -> String {
+    let input = match input_arg() {
+        None => input::load_input(day),
+        Some(path) => input::load_custom(&path),
+    };
+
+    input.unwrap_or_else(|e| {
+        eprintln!("{ANSI_RED}Could not load the input: {e}{ANSI_RESET}");
+        process::exit(1);
+    })
+}
+
+/// Returns the value of the `--input` argument, if the binary was invoked with a non-default input.
+#[must_use]
+pub fn input_arg() -> Option<String> {
+    let args: Vec<String> = env::args().collect();
+    let index = args.iter().position(|x| x == "--input")?;
+    args.get(index + 1).cloned()
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
 pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd
-        .join("data")
-        .join(folder)
-        .join(format!("{day}-{part}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    input::load_file(folder, day, Some(part)).unwrap_or_else(|e| panic!("{e}"))
 }
 
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+/// Alternatively, the second parameter names a type implementing [`Solution`], which is run instead of the free functions.
+/// A trailing `generator = <function>` registers an input generator, see [`generate`].
+/// A trailing `variants = [(<part>, "<name>", <function>), ...]` registers alternative implementations of the parts,
+/// see [`variants`]. Variants are not supported with a [`Solution`] type.
 #[macro_export]
 macro_rules! solution {
-    ($day:expr) => {
-        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]);
+    ($day:expr $(, generator = $generator:path)? $(, variants = [$(($vpart:literal, $vname:literal, $vfunc:expr)),* $(,)?])?) => {
+        $crate::solution!(@impl $day, [$($generator;)?] [$($([$vpart, $vname, $vfunc])*)?] [part_one, 1] [part_two, 2]);
     };
-    ($day:expr, 1) => {
-        $crate::solution!(@impl $day, [part_one, 1]);
+    ($day:expr, 1 $(, generator = $generator:path)? $(, variants = [$(($vpart:literal, $vname:literal, $vfunc:expr)),* $(,)?])?) => {
+        $crate::solution!(@impl $day, [$($generator;)?] [$($([$vpart, $vname, $vfunc])*)?] [part_one, 1]);
     };
-    ($day:expr, 2) => {
-        $crate::solution!(@impl $day, [part_two, 2]);
+    ($day:expr, 2 $(, generator = $generator:path)? $(, variants = [$(($vpart:literal, $vname:literal, $vfunc:expr)),* $(,)?])?) => {
+        $crate::solution!(@impl $day, [$($generator;)?] [$($([$vpart, $vname, $vfunc])*)?] [part_two, 2]);
     };
+    ($day:expr, $solution:ty $(, generator = $generator:path)?) => {
+        /// The current day.
+        const DAY: $crate::template::Day = $crate::day!($day);
+
+        #[cfg(feature = "dhat-heap")]
+        #[global_allocator]
+        static ALLOC: dhat::Alloc = dhat::Alloc;
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+        fn main() {
+            use $crate::template::runner::*;
+            // reports an invalid configuration before anything is printed.
+            $crate::template::config::get();
+            configure_threads();
+            let input = $crate::solution!(@input [$($generator;)?]);
+            print_bench_settings();
+            run_solution::<$solution>(&input, DAY);
+        }
+    };
+
+    (@impl $day:expr, [$($generator:path;)?] [$([$vpart:expr, $vname:expr, $vfunc:expr])*] $( [$func:expr, $part:expr] )*) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
@@ -58,8 +144,38 @@ macro_rules! solution {
 
         fn main() {
             use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+            // reports an invalid configuration before anything is printed.
+            $crate::template::config::get();
+            configure_threads();
+            let input = $crate::solution!(@input [$($generator;)?]);
+            let variants: Vec<$crate::template::variants::Variant> = vec![
+                $( $crate::template::variants::Variant::new($vpart, $vname, $vfunc), )*
+            ];
+
+            if $crate::template::variants::compare_requested() {
+                let defaults = [
+                    $( $crate::template::variants::Variant::new($part, $crate::template::variants::DEFAULT, $func), )*
+                ];
+                compare_variants(defaults.into_iter().chain(variants), &input);
+                return;
+            }
+
+            print_bench_settings();
+            let selected = $crate::template::variants::selected(&variants);
+            $crate::template::variants::print_selection(&variants, selected, &[$($part),*]);
+
+            $(
+                // the default is run as is, so that its answer is not formatted while it is benchmarked.
+                match $crate::template::variants::find(&variants, $part, selected) {
+                    Some(variant) => run_part(|input| variant.run(input), input.as_str(), DAY, $part),
+                    None => run_part($func, &input, DAY, $part),
+                }
+            )*
         }
     };
+
+    (@input [$($generator:path;)?]) => {{
+        let generator: Option<$crate::template::generate::Generator> = None $( .or(Some($generator)) )?;
+        $crate::template::generate::read_input(DAY, generator)
+    }};
 }
@@ -0,0 +1,176 @@
+//! Module that recognizes capital letters drawn as ASCII art, which some puzzles use as their answer.
+//!
+//! Two fonts are known: letters that are 4 pixels wide and 6 tall, and letters that are 6 pixels wide and 10 tall.
+//! Lit pixels are drawn with `#` or `█`, anything else counts as unlit.
+//! Letters are separated by at least one unlit column, so they are matched regardless of their spacing.
+
+/// Letters that are 6 pixels tall, most of them 4 pixels wide.
+const FONT_6: [(char, &[&str]); 18] = [
+    ('A', &[".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', &["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', &[".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', &["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', &["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', &[".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', &["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', &[".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', &["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', &["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', &["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', &[".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', &["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', &["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', &[".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', &["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', &["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', &["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Letters that are 10 pixels tall and 6 pixels wide.
+#[rustfmt::skip]
+const FONT_10: [(char, &[&str]); 15] = [
+    ('A', &["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', &["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', &[".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', &[".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', &["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', &["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', &["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', &["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', &["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', &["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', &["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', &["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', &["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+/// Pixels of a letter, as rows of lit and unlit pixels without surrounding unlit columns.
+type Glyph = Vec<Vec<bool>>;
+
+/// Recognizes the letters drawn in `art`. Returns `None` if the art does not have the height of a known font
+/// or contains a shape that is not a known letter.
+#[must_use]
+pub fn recognize(art: &str) -> Option<String> {
+    let rows: Vec<Vec<bool>> = art
+        .lines()
+        .map(|line| line.chars().map(is_lit).collect())
+        .skip_while(|row: &Vec<bool>| !row.contains(&true))
+        .collect();
+
+    let height = rows.iter().rposition(|row| row.contains(&true))? + 1;
+    let rows = &rows[..height];
+
+    let font: &[(char, &[&str])] = match height {
+        6 => &FONT_6,
+        10 => &FONT_10,
+        _ => return None,
+    };
+
+    glyphs(rows)
+        .iter()
+        .map(|glyph| {
+            font.iter()
+                .find(|(_, pixels)| &parse_glyph(pixels) == glyph)
+                .map(|(letter, _)| *letter)
+        })
+        .collect()
+}
+
+/// Returns the recognized letters if `answer` is ASCII art, otherwise the answer itself.
+#[must_use]
+pub fn answer(answer: &str) -> String {
+    if answer.contains('\n') {
+        recognize(answer).unwrap_or_else(|| answer.to_string())
+    } else {
+        answer.to_string()
+    }
+}
+
+fn is_lit(c: char) -> bool {
+    matches!(c, '#' | '█')
+}
+
+fn parse_glyph(pixels: &[&str]) -> Glyph {
+    let rows: Vec<Vec<bool>> = pixels
+        .iter()
+        .map(|row| row.chars().map(is_lit).collect())
+        .collect();
+
+    glyphs(&rows).swap_remove(0)
+}
+
+/// Splits rows of pixels into glyphs at columns that are unlit in every row.
+fn glyphs(rows: &[Vec<bool>]) -> Vec<Glyph> {
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let is_lit_column = |x: usize| rows.iter().any(|row| row.get(x) == Some(&true));
+
+    let mut glyphs = vec![];
+    let mut x = 0;
+
+    while x < width {
+        if !is_lit_column(x) {
+            x += 1;
+            continue;
+        }
+
+        let start = x;
+        while x < width && is_lit_column(x) {
+            x += 1;
+        }
+
+        glyphs.push(
+            rows.iter()
+                .map(|row| (start..x).map(|i| row.get(i) == Some(&true)).collect())
+                .collect(),
+        );
+    }
+
+    glyphs
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{answer, recognize, FONT_10, FONT_6};
+
+    /// Draws the letters of a font next to each other, separated by one unlit column.
+    fn draw(font: &[(char, &[&str])]) -> String {
+        let height = font[0].1.len();
+        (0..height)
+            .map(|y| {
+                font.iter()
+                    .map(|(_, pixels)| pixels[y])
+                    .collect::<Vec<_>>()
+                    .join(".")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn recognizes_all_letters() {
+        assert_eq!(
+            recognize(&draw(&FONT_6)).as_deref(),
+            Some("ABCEFGHIJKLOPRSUYZ")
+        );
+        assert_eq!(
+            recognize(&draw(&FONT_10)).as_deref(),
+            Some("ABCEFGHJKLNPRXZ")
+        );
+    }
+
+    #[test]
+    fn recognizes_other_pixels_and_spacing() {
+        let art = "\n█  █  ███   \n█  █  █  █  \n████  ███   \n█  █  █  █  \n█  █  █  █  \n█  █  ███   \n";
+        assert_eq!(recognize(art).as_deref(), Some("HB"));
+    }
+
+    #[test]
+    fn rejects_unknown_shapes() {
+        assert_eq!(recognize("#..#\n.##.\n#..#"), None);
+        assert_eq!(recognize("####\n#..#\n####\n#..#\n#..#\n####"), None);
+        assert_eq!(answer("####\n#..#"), "####\n#..#");
+        assert_eq!(answer("42"), "42");
+    }
+}
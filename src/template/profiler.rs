@@ -0,0 +1,122 @@
+/// Module that profiles solution parts with an in-process sampling profiler.
+///
+/// It is only compiled with the `cpu-profile` feature, which `cargo solve --profile-cpu` enables.
+/// Each profile is written as folded stacks, the input format of most flamegraph tools, and as a flamegraph SVG.
+use std::{
+    fmt::Write as _,
+    fs::{self, File},
+    hint::black_box,
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::template::Day;
+
+/// Directory that profiles are written to.
+pub const PROFILING_DIR: &str = "target/profiles";
+
+/// Sampling frequency in Hz. A prime avoids sampling in lockstep with periodic work.
+const FREQUENCY: i32 = 997;
+
+/// Libraries whose frames cannot be unwound safely from a signal handler.
+const BLOCKLIST: &[&str] = &["libc", "libgcc", "pthread", "vdso"];
+
+#[derive(Debug)]
+pub enum Error {
+    Profiler(String),
+    IO(io::Error),
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+impl From<pprof::Error> for Error {
+    fn from(e: pprof::Error) -> Self {
+        Error::Profiler(e.to_string())
+    }
+}
+
+pub struct Profile {
+    pub flamegraph: PathBuf,
+    pub folded: PathBuf,
+    pub samples: isize,
+    pub runs: u32,
+}
+
+/// Runs the function repeatedly for at least `duration` while sampling its stacks, then writes the profile.
+pub fn profile<I: Clone, T>(
+    func: impl Fn(I) -> T,
+    input: &I,
+    day: Day,
+    part: u8,
+    duration: Duration,
+) -> Result<Profile, Error> {
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(FREQUENCY)
+        .blocklist(BLOCKLIST)
+        .build()?;
+
+    let timer = Instant::now();
+    let mut runs: u32 = 0;
+
+    while runs == 0 || timer.elapsed() < duration {
+        black_box(func(black_box(input.clone())));
+        runs += 1;
+    }
+
+    let report = guard.report().build()?;
+    drop(guard);
+
+    let lines = fold(&report);
+    let samples = report.data.values().sum();
+
+    let dir = Path::new(PROFILING_DIR);
+    fs::create_dir_all(dir)?;
+
+    let folded = dir.join(format!("{day}-{part}.folded"));
+    fs::write(&folded, lines.join("\n") + "\n")?;
+
+    let flamegraph = dir.join(format!("{day}-{part}.svg"));
+    let mut options = inferno::flamegraph::Options::default();
+    options.title = format!("Day {day}, part {part}");
+    inferno::flamegraph::from_lines(
+        &mut options,
+        lines.iter().map(String::as_str),
+        BufWriter::new(File::create(&flamegraph)?),
+    )
+    .map_err(|e| Error::Profiler(e.to_string()))?;
+
+    Ok(Profile {
+        flamegraph,
+        folded,
+        samples,
+        runs,
+    })
+}
+
+/// Formats the sampled stacks as folded lines, e.g. `main;part_one;parse 12`, sorted for stable output.
+fn fold(report: &pprof::Report) -> Vec<String> {
+    let mut lines: Vec<String> = report
+        .data
+        .iter()
+        .map(|(frames, count)| {
+            let mut line = frames.thread_name_or_id();
+
+            // frames are ordered from the innermost call outwards.
+            for symbol in frames.frames.iter().rev().flat_map(|f| f.iter().rev()) {
+                // semicolons separate frames, but also appear in array types like `[u8; 4]`.
+                let _ = write!(line, ";{}", symbol.to_string().replace(';', ","));
+            }
+
+            let _ = write!(line, " {count}");
+            line
+        })
+        .collect();
+
+    lines.sort();
+    lines
+}
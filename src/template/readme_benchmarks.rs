@@ -1,10 +1,24 @@
 /// Module that updates the readme me with timing information.
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
-use std::{fs, io};
+///
+/// Updates merge with the existing table: days that were not measured keep their row.
+/// The table records a hash of each day's source file, so rows whose solution changed since can be marked as stale.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    fs, io,
+    str::FromStr,
+    time::Duration,
+};
 
-use crate::template::Day;
+use crate::template::{all_days, config::Config, Day};
 
-static MARKER: &str = "<!--- benchmarking table --->";
+const SOURCES_PREFIX: &str = "<!--- sources:";
+const SOURCES_SUFFIX: &str = "--->";
+const STALE: &str = "*(stale)*";
+
+/// Hashes of the solutions' source files by day.
+pub type SourceHashes = BTreeMap<Day, u64>;
 
 #[derive(Debug)]
 pub enum Error {
@@ -18,26 +32,407 @@ impl From<std::io::Error> for Error {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Timings {
     pub day: Day,
-    pub part_1: Option<String>,
-    pub part_2: Option<String>,
+    pub parse: Option<Timing>,
+    pub part_1: Option<Timing>,
+    pub part_2: Option<Timing>,
     pub total_nanos: f64,
+    /// Heap usage of both parts, if the day was profiled with dhat.
+    pub heap: Option<HeapUsage>,
+    /// Steps that returned an error or panicked. They have no timing.
+    pub failures: Vec<Failure>,
 }
 
-pub struct TablePosition {
-    pos_start: usize,
-    pos_end: usize,
+impl Timings {
+    /// The timing of a step, if it was measured.
+    #[must_use]
+    pub fn step(&self, step: Step) -> Option<&Timing> {
+        match step {
+            Step::Parse => self.parse.as_ref(),
+            Step::Part(1) => self.part_1.as_ref(),
+            Step::Part(2) => self.part_2.as_ref(),
+            Step::Part(_) => None,
+        }
+    }
+}
+
+/// A step that returned an error or panicked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Failure {
+    pub step: Step,
+    pub message: String,
+    /// Whether the step panicked, the message then includes the location of the panic.
+    pub panicked: bool,
+}
+
+/// A step of a solution, either parsing the input or one of the parts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    Parse,
+    Part(u8),
+}
+
+impl Step {
+    /// The steps of a solution in the order they run.
+    pub const ALL: [Step; 3] = [Self::Parse, Self::Part(1), Self::Part(2)];
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse => write!(f, "Parse"),
+            Self::Part(part) => write!(f, "Part {part}"),
+        }
+    }
+}
+
+impl FromStr for Step {
+    type Err = String;
+
+    /// Parses the format written by [`Display`], e.g. `Parse` or `Part 1`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("Part ") {
+            None if s == "Parse" => Ok(Self::Parse),
+            Some(part) => part
+                .parse()
+                .map(Self::Part)
+                .map_err(|_| format!("invalid step `{s}`")),
+            None => Err(format!("invalid step `{s}`")),
+        }
+    }
+}
+
+/// The measured execution time of a single step of a solution.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Timing {
+    /// The duration as printed by the runner, e.g. `74.13ns`.
+    pub formatted: String,
+    pub nanos: f64,
+    pub samples: u128,
+    /// Standard deviation of the samples, if known.
+    pub deviation_nanos: Option<f64>,
+    /// Duration of the first, cold run, if the step was benchmarked.
+    /// The other fields describe the warm runs of the benchmark.
+    pub cold_nanos: Option<f64>,
+}
+
+/// Heap usage of a solution as recorded by dhat.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HeapUsage {
+    pub total_bytes: u64,
+    pub total_blocks: u64,
+    /// Maximum size of the heap at any point in time.
+    pub peak_bytes: u64,
+}
+
+impl HeapUsage {
+    /// Combines the usage of two runs: allocations add up, the peak is the larger of both.
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            total_bytes: self.total_bytes + other.total_bytes,
+            total_blocks: self.total_blocks + other.total_blocks,
+            peak_bytes: self.peak_bytes.max(other.peak_bytes),
+        }
+    }
+}
+
+impl Display for HeapUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} bytes in {} blocks, {} bytes at peak",
+            self.total_bytes, self.total_blocks, self.peak_bytes
+        )
+    }
+}
+
+impl FromStr for HeapUsage {
+    type Err = String;
+
+    /// Parses the format written by [`Display`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("invalid heap usage `{s}`");
+
+        let numbers: Vec<u64> = s
+            .split_whitespace()
+            .filter_map(|word| word.parse().ok())
+            .collect();
+
+        match numbers[..] {
+            [total_bytes, total_blocks, peak_bytes] => Ok(Self {
+                total_bytes,
+                total_blocks,
+                peak_bytes,
+            }),
+            _ => Err(error()),
+        }
+    }
+}
+
+/// A column of the benchmark table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Column {
+    Day,
+    Parse,
+    Part1,
+    Part2,
+    /// Duration of the first, cold run of part 1. The `Part 1` column shows the warm average.
+    ColdPart1,
+    /// Duration of the first, cold run of part 2.
+    ColdPart2,
+    Total,
+    Samples,
+    /// The day's share of the total runtime, rendered as a bar.
+    Share,
+    /// Bytes and blocks allocated by both parts, requires profiling with dhat.
+    Allocated,
+    /// Peak heap size of the day, requires profiling with dhat.
+    PeakHeap,
+}
+
+impl FromStr for Column {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(Self::Day),
+            "parse" => Ok(Self::Parse),
+            "part_1" => Ok(Self::Part1),
+            "part_2" => Ok(Self::Part2),
+            "cold_part_1" => Ok(Self::ColdPart1),
+            "cold_part_2" => Ok(Self::ColdPart2),
+            "total" => Ok(Self::Total),
+            "samples" => Ok(Self::Samples),
+            "share" => Ok(Self::Share),
+            "allocated" => Ok(Self::Allocated),
+            "peak_heap" => Ok(Self::PeakHeap),
+            _ => Err(format!(
+                "unknown column `{s}`, expecting one of: day, parse, part_1, part_2, cold_part_1, cold_part_2, total, samples, share, allocated, peak_heap"
+            )),
+        }
+    }
+}
+
+impl Column {
+    fn header(self) -> &'static str {
+        match self {
+            Column::Day => "Day",
+            Column::Parse => "Parse",
+            Column::Part1 => "Part 1",
+            Column::Part2 => "Part 2",
+            Column::ColdPart1 => "Part 1 (cold)",
+            Column::ColdPart2 => "Part 2 (cold)",
+            Column::Total => "Total",
+            Column::Samples => "Samples",
+            Column::Share => "Share",
+            Column::Allocated => "Allocated",
+            Column::PeakHeap => "Peak heap",
+        }
+    }
+
+    fn from_header(header: &str) -> Option<Self> {
+        [
+            Column::Day,
+            Column::Parse,
+            Column::Part1,
+            Column::Part2,
+            Column::ColdPart1,
+            Column::ColdPart2,
+            Column::Total,
+            Column::Samples,
+            Column::Share,
+            Column::Allocated,
+            Column::PeakHeap,
+        ]
+        .into_iter()
+        .find(|c| c.header() == header)
+    }
+
+    fn cell(self, timings: &Timings, total_nanos: f64, config: &Config) -> String {
+        let format_timing = |timing: &Option<Timing>| {
+            timing
+                .as_ref()
+                .map_or("-".into(), |t| format!("`{}`", t.formatted))
+        };
+        let format_cold = |timing: &Option<Timing>| {
+            timing
+                .as_ref()
+                .and_then(|t| t.cold_nanos)
+                .map_or("-".into(), |nanos| format!("`{}`", format_nanos(nanos)))
+        };
+
+        match self {
+            Column::Day => {
+                let day = timings.day;
+                let source = format!("./{}", config.bin_path(day).display());
+                match config.year {
+                    Some(year) => format!(
+                        "[Day {}](https://adventofcode.com/{year}/day/{}) · [source]({source})",
+                        day.into_inner(),
+                        day.into_inner()
+                    ),
+                    None => format!("[Day {}]({source})", day.into_inner()),
+                }
+            }
+            Column::Parse => format_timing(&timings.parse),
+            Column::Part1 => format_timing(&timings.part_1),
+            Column::Part2 => format_timing(&timings.part_2),
+            Column::ColdPart1 => format_cold(&timings.part_1),
+            Column::ColdPart2 => format_cold(&timings.part_2),
+            Column::Total => format!("`{}`", format_nanos(timings.total_nanos)),
+            Column::Samples => [&timings.parse, &timings.part_1, &timings.part_2]
+                .into_iter()
+                .flatten()
+                .map(|t| t.samples.to_string())
+                .collect::<Vec<_>>()
+                .join(" / "),
+            Column::Share => {
+                if total_nanos > 0.0 {
+                    let share = timings.total_nanos / total_nanos;
+                    format!("`{}` {:.1}%", bar(share, 10), share * 100.0)
+                } else {
+                    "-".into()
+                }
+            }
+            Column::Allocated => timings.heap.map_or("-".into(), |heap| {
+                format!(
+                    "`{}` in {} blocks",
+                    format_bytes(heap.total_bytes),
+                    heap.total_blocks
+                )
+            }),
+            Column::PeakHeap => timings.heap.map_or("-".into(), |heap| {
+                format!("`{}`", format_bytes(heap.peak_bytes))
+            }),
+        }
+    }
+}
+
+/// Order of the rows in the benchmark table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sort {
+    Day,
+    /// Most expensive days first.
+    Cost,
+}
+
+impl FromStr for Sort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(Self::Day),
+            "cost" => Ok(Self::Cost),
+            _ => Err(format!(
+                "unknown sort order `{s}`, expecting `day` or `cost`"
+            )),
+        }
+    }
+}
+
+/// Renders a bar of `width` characters that is filled according to `share` (0.0 to 1.0), using eighth blocks.
+fn bar(share: f64, width: usize) -> String {
+    const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let eighths = (share.clamp(0.0, 1.0) * (width * 8) as f64).round() as usize;
+
+    let mut s = "█".repeat(eighths / 8);
+    if !eighths.is_multiple_of(8) {
+        s.push(PARTIAL_BLOCKS[eighths % 8]);
+    }
+
+    let len = s.chars().count();
+    s.extend(std::iter::repeat_n(' ', width - len));
+    s
+}
+
+/// Parses a duration as formatted by the runner, e.g. `74.13ns` or `1.2ms`, into nanoseconds.
+#[must_use]
+pub fn parse_nanos(s: &str) -> Option<f64> {
+    // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
+    let parse_to_float = |postfix: &str| s.split(postfix).next()?.trim().parse::<f64>().ok();
+
+    match s {
+        s if s.contains("ns") => parse_to_float("ns"),
+        s if s.contains("µs") => parse_to_float("µs").map(|x| x * 1000_f64),
+        s if s.contains("ms") => parse_to_float("ms").map(|x| x * 1_000_000_f64),
+        _ => parse_to_float("s").map(|x| x * 1_000_000_000_f64),
+    }
 }
 
+/// Hashes the source files of all days that have a solution, using 64-bit FNV-1a.
 #[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+pub fn source_hashes(config: &Config) -> SourceHashes {
+    all_days()
+        .filter_map(|day| {
+            let content = fs::read(config.bin_path(day)).ok()?;
+            let hash = content
+                .iter()
+                .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                    (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+                });
+            Some((day, hash))
+        })
+        .collect()
 }
 
-fn locate_table(readme: &str) -> Result<TablePosition, Error> {
-    let matches: Vec<_> = readme.match_indices(MARKER).collect();
+const BYTE_UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+/// Formats a number of bytes with binary prefixes, e.g. `1.5 KiB`.
+#[must_use]
+pub fn format_bytes(bytes: u64) -> String {
+    #[allow(clippy::cast_precision_loss)]
+    let mut value = bytes as f64;
+    let mut unit = 0;
+
+    while value >= 1024.0 && unit < BYTE_UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", BYTE_UNITS[unit])
+    }
+}
+
+/// Parses a number of bytes as formatted by [`format_bytes`]. Precision is lost for values of a kibibyte or more.
+fn parse_bytes(s: &str) -> Option<u64> {
+    let (value, unit) = s.trim().split_once(' ')?;
+    let exponent = BYTE_UNITS.iter().position(|u| *u == unit)?;
+    let value: f64 = value.parse().ok()?;
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_possible_wrap
+    )]
+    Some((value * 1024_f64.powi(exponent as i32)).round() as u64)
+}
+
+/// Formats nanoseconds with the same precision the runner uses for durations.
+fn format_nanos(nanos: f64) -> String {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let duration = Duration::from_nanos(nanos as u64);
+    format!("{duration:.1?}")
+}
+
+pub struct TablePosition {
+    pos_start: usize,
+    pos_end: usize,
+}
+
+fn locate_table(readme: &str, marker: &str) -> Result<TablePosition, Error> {
+    let matches: Vec<_> = readme.match_indices(marker).collect();
 
     if matches.len() > 2 {
         return Err(Error::Parser(
@@ -58,104 +453,426 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     Ok(TablePosition { pos_start, pos_end })
 }
 
-fn construct_table(prefix: &str, timings: Vec<Timings>, total_millis: f64) -> String {
+/// A row of the benchmark table.
+struct Row {
+    timings: Timings,
+    /// Hash of the source file at the time the row was measured.
+    source_hash: Option<u64>,
+    /// Whether the source file changed since the row was measured.
+    stale: bool,
+}
+
+/// Rows parsed back from an existing benchmark table, along with the recorded source hashes.
+struct ParsedTable {
+    timings: Vec<Timings>,
+    source_hashes: SourceHashes,
+}
+
+fn construct_table(prefix: &str, mut rows: Vec<Row>, total_millis: f64, config: &Config) -> String {
     let header = format!("{prefix} Benchmarks");
+    let columns = &config.readme_columns;
 
     let mut lines: Vec<String> = vec![
-        MARKER.into(),
+        config.readme_marker.clone(),
         header,
         String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
+        format!(
+            "| {} |",
+            columns
+                .iter()
+                .map(|c| c.header())
+                .collect::<Vec<_>>()
+                .join(" | ")
+        ),
+        format!("| {} |", vec![":---:"; columns.len()].join(" | ")),
     ];
 
-    for timing in timings {
-        let path = get_path_for_bin(timing.day);
-        lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
-            timing.day.into_inner(),
-            path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
-        ));
+    match config.readme_sort {
+        Sort::Day => rows.sort_by_key(|r| r.timings.day),
+        Sort::Cost => rows.sort_by(|a, b| b.timings.total_nanos.total_cmp(&a.timings.total_nanos)),
+    }
+
+    let total_nanos: f64 = rows.iter().map(|r| r.timings.total_nanos).sum();
+
+    for row in &rows {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|c| {
+                let cell = c.cell(&row.timings, total_nanos, config);
+                if row.stale && *c == Column::Day {
+                    format!("{cell} {STALE}")
+                } else {
+                    cell
+                }
+            })
+            .collect();
+        lines.push(format!("| {} |", cells.join(" | ")));
     }
 
     lines.push(String::new());
     lines.push(format!("**Total: {total_millis:.2}ms**"));
-    lines.push(MARKER.into());
+
+    let hashes: Vec<String> = rows
+        .iter()
+        .filter_map(|r| Some(format!("{}={:016x}", r.timings.day, r.source_hash?)))
+        .collect();
+    if !hashes.is_empty() {
+        lines.push(format!(
+            "{SOURCES_PREFIX} {} {SOURCES_SUFFIX}",
+            hashes.join(" ")
+        ));
+    }
+
+    lines.push(config.readme_marker.clone());
 
     lines.join("\n")
 }
 
-fn update_content(s: &mut String, timings: Vec<Timings>, total_millis: f64) -> Result<(), Error> {
-    let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
+/// Parses the rows of an existing benchmark table. Rows can only be recovered if the table has a `Day` column.
+fn parse_table(table: &str) -> ParsedTable {
+    let mut timings = vec![];
+    let mut source_hashes = SourceHashes::new();
+    let mut columns: Option<Vec<Option<Column>>> = None;
+
+    let split_cells = |line: &str| -> Vec<String> {
+        line.trim()
+            .trim_start_matches('|')
+            .trim_end_matches('|')
+            .split('|')
+            .map(|c| c.trim().to_string())
+            .collect()
+    };
+
+    for line in table.lines().map(str::trim) {
+        if let Some(sources) = line.strip_prefix(SOURCES_PREFIX) {
+            source_hashes.extend(
+                sources
+                    .trim_end_matches(SOURCES_SUFFIX)
+                    .split_whitespace()
+                    .filter_map(|entry| {
+                        let (day, hash) = entry.split_once('=')?;
+                        Some((day.parse().ok()?, u64::from_str_radix(hash, 16).ok()?))
+                    }),
+            );
+            continue;
+        }
+
+        if !line.starts_with('|') || line.contains(":---:") {
+            continue;
+        }
+
+        match &columns {
+            None => {
+                columns = Some(
+                    split_cells(line)
+                        .iter()
+                        .map(|h| Column::from_header(h))
+                        .collect(),
+                );
+            }
+            Some(columns) => {
+                if let Some(t) = parse_row(columns, &split_cells(line)) {
+                    timings.push(t);
+                }
+            }
+        }
+    }
+
+    ParsedTable {
+        timings,
+        source_hashes,
+    }
+}
+
+fn parse_row(columns: &[Option<Column>], cells: &[String]) -> Option<Timings> {
+    let cell = |column: Column| {
+        let i = columns.iter().position(|c| *c == Some(column))?;
+        cells.get(i).map(String::as_str)
+    };
+
+    let parse_timing = |column: Column| {
+        let formatted = cell(column)?.trim_matches('`');
+        Some(Timing {
+            formatted: formatted.into(),
+            nanos: parse_nanos(formatted)?,
+            samples: 0,
+            deviation_nanos: None,
+            cold_nanos: None,
+        })
+    };
+
+    let day_cell = cell(Column::Day)?;
+    let day_number: String = day_cell
+        .split_once("Day ")?
+        .1
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+
+    // without a warm column, the cold run is the best known timing of a part.
+    let parse_part = |warm: Column, cold: Column| {
+        let cold_nanos = cell(cold).and_then(|c| parse_nanos(c.trim_matches('`')));
+        let timing = parse_timing(warm).or_else(|| parse_timing(cold))?;
+        Some(Timing {
+            cold_nanos,
+            ..timing
+        })
+    };
+
+    let mut timings = Timings {
+        day: day_number.parse().ok()?,
+        parse: parse_timing(Column::Parse),
+        part_1: parse_part(Column::Part1, Column::ColdPart1),
+        part_2: parse_part(Column::Part2, Column::ColdPart2),
+        total_nanos: 0_f64,
+        heap: None,
+        failures: vec![],
+    };
+
+    timings.total_nanos = cell(Column::Total)
+        .and_then(|t| parse_nanos(t.trim_matches('`')))
+        .unwrap_or_else(|| {
+            [&timings.parse, &timings.part_1, &timings.part_2]
+                .into_iter()
+                .flatten()
+                .map(|t| t.nanos)
+                .sum()
+        });
+
+    let allocated = cell(Column::Allocated).and_then(|c| {
+        let (bytes, blocks) = c.split_once(" in ")?;
+        Some((
+            parse_bytes(bytes.trim_matches('`'))?,
+            blocks.trim_end_matches(" blocks").parse().ok()?,
+        ))
+    });
+    let peak = cell(Column::PeakHeap).and_then(|c| parse_bytes(c.trim_matches('`')));
+
+    if allocated.is_some() || peak.is_some() {
+        let (total_bytes, total_blocks) = allocated.unwrap_or_default();
+        timings.heap = Some(HeapUsage {
+            total_bytes,
+            total_blocks,
+            peak_bytes: peak.unwrap_or_default(),
+        });
+    }
+
+    if let Some(samples) = cell(Column::Samples) {
+        let mut samples = samples.split(" / ").filter_map(|s| s.trim().parse().ok());
+        for t in [&mut timings.parse, &mut timings.part_1, &mut timings.part_2]
+            .into_iter()
+            .flatten()
+        {
+            t.samples = samples.next().unwrap_or(0);
+        }
+    }
+
+    Some(timings)
+}
+
+/// Merges `timings`, which were measured on sources with `measured_hashes`, into the existing table.
+/// Days that were not measured keep their previous row. Rows are marked as stale if their source hash
+/// differs from `current_hashes`.
+fn update_content(
+    s: &mut String,
+    timings: Vec<Timings>,
+    total_millis: f64,
+    measured_hashes: &SourceHashes,
+    current_hashes: &SourceHashes,
+    config: &Config,
+) -> Result<(), Error> {
+    let positions = locate_table(s, &config.readme_marker)?;
+    let existing = parse_table(&s[positions.pos_start..positions.pos_end]);
+
+    let measured: BTreeSet<Day> = timings.iter().map(|t| t.day).collect();
+    let is_stale = |day: Day, source_hash: Option<u64>| {
+        source_hash.is_some() && source_hash != current_hashes.get(&day).copied()
+    };
+
+    let kept: Vec<Row> = existing
+        .timings
+        .into_iter()
+        .filter(|t| !measured.contains(&t.day))
+        .map(|t| {
+            let source_hash = existing.source_hashes.get(&t.day).copied();
+            Row {
+                stale: is_stale(t.day, source_hash),
+                source_hash,
+                timings: t,
+            }
+        })
+        .collect();
+
+    let kept_millis = kept.iter().map(|r| r.timings.total_nanos).sum::<f64>() / 1_000_000_f64;
+
+    let rows = timings
+        .into_iter()
+        .map(|t| {
+            let source_hash = measured_hashes.get(&t.day).copied();
+            Row {
+                stale: is_stale(t.day, source_hash),
+                source_hash,
+                timings: t,
+            }
+        })
+        .chain(kept)
+        .collect();
+
+    let table = construct_table("##", rows, total_millis + kept_millis, config);
     s.replace_range(positions.pos_start..positions.pos_end, &table);
     Ok(())
 }
 
-pub fn update(timings: Vec<Timings>, total_millis: f64) -> Result<(), Error> {
-    let path = "README.md";
+/// Writes the timings to the benchmark table of the readme. `measured_hashes` are the hashes of the sources the timings were measured on.
+pub fn update(
+    timings: Vec<Timings>,
+    total_millis: f64,
+    measured_hashes: &SourceHashes,
+    config: &Config,
+) -> Result<(), Error> {
+    let path = &config.readme_path;
     let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
-    update_content(&mut readme, timings, total_millis)?;
+    update_content(
+        &mut readme,
+        timings,
+        total_millis,
+        measured_hashes,
+        &source_hashes(config),
+        config,
+    )?;
     fs::write(path, &readme)?;
     Ok(())
 }
 
+/// Timings shared by the tests of all modules that work with them, so that new fields only need to be added here.
 #[cfg(feature = "test_lib")]
-mod tests {
-    use super::{update_content, Timings, MARKER};
-    use crate::day;
+pub(crate) mod fixtures {
+    use super::{Timing, Timings};
+    use crate::{day, template::Day};
 
-    fn get_mock_timings() -> Vec<Timings> {
+    /// A step that was benchmarked with 100 samples.
+    pub(crate) fn timing(formatted: &str, nanos: f64) -> Timing {
+        Timing {
+            formatted: formatted.into(),
+            nanos,
+            samples: 100,
+            deviation_nanos: None,
+            cold_nanos: None,
+        }
+    }
+
+    /// A day that ran the given parts, without parse step, heap usage or failures.
+    pub(crate) fn timings(day: Day, part_1: Option<Timing>, part_2: Option<Timing>) -> Timings {
+        Timings {
+            day,
+            parse: None,
+            total_nanos: part_1.iter().chain(&part_2).map(|t| t.nanos).sum(),
+            part_1,
+            part_2,
+            heap: None,
+            failures: vec![],
+        }
+    }
+
+    /// Days 1, 2 and 4, with parts between 10 and 50ms.
+    pub(crate) fn get_mock_timings() -> Vec<Timings> {
         vec![
-            Timings {
-                day: day!(1),
-                part_1: Some("10ms".into()),
-                part_2: Some("20ms".into()),
-                total_nanos: 3e+10,
-            },
-            Timings {
-                day: day!(2),
-                part_1: Some("30ms".into()),
-                part_2: Some("40ms".into()),
-                total_nanos: 7e+10,
-            },
-            Timings {
-                day: day!(4),
-                part_1: Some("40ms".into()),
-                part_2: Some("50ms".into()),
-                total_nanos: 9e+10,
-            },
+            timings(
+                day!(1),
+                Some(timing("10ms", 1e7)),
+                Some(timing("20ms", 2e7)),
+            ),
+            timings(
+                day!(2),
+                Some(timing("30ms", 3e7)),
+                Some(timing("40ms", 4e7)),
+            ),
+            timings(
+                day!(4),
+                Some(timing("40ms", 4e7)),
+                Some(timing("50ms", 5e7)),
+            ),
         ]
     }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        bar,
+        fixtures::{get_mock_timings, timing, timings},
+        format_bytes, parse_nanos, update_content, Column, HeapUsage, Sort, SourceHashes,
+    };
+    use crate::{day, template::config::Config};
+
+    const MARKER: &str = "<!--- benchmarking table --->";
 
     #[test]
     #[should_panic]
     fn errors_if_marker_not_present() {
         let mut s = "# readme".to_string();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            get_mock_timings(),
+            190.0,
+            &SourceHashes::new(),
+            &SourceHashes::new(),
+            &Config::default(),
+        )
+        .unwrap();
     }
 
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
         let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            get_mock_timings(),
+            190.0,
+            &SourceHashes::new(),
+            &SourceHashes::new(),
+            &Config::default(),
+        )
+        .unwrap();
     }
 
     #[test]
     fn updates_empty_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            get_mock_timings(),
+            190.0,
+            &SourceHashes::new(),
+            &SourceHashes::new(),
+            &Config::default(),
+        )
+        .unwrap();
         assert_eq!(s.contains("## Benchmarks"), true);
     }
 
     #[test]
     fn updates_existing_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            get_mock_timings(),
+            190.0,
+            &SourceHashes::new(),
+            &SourceHashes::new(),
+            &Config::default(),
+        )
+        .unwrap();
+        update_content(
+            &mut s,
+            get_mock_timings(),
+            190.0,
+            &SourceHashes::new(),
+            &SourceHashes::new(),
+            &Config::default(),
+        )
+        .unwrap();
         assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
         assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
     }
@@ -163,7 +880,15 @@ mod tests {
     #[test]
     fn format_benchmarks() {
         let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            get_mock_timings(),
+            190.0,
+            &SourceHashes::new(),
+            &SourceHashes::new(),
+            &Config::default(),
+        )
+        .unwrap();
         let expected = [
             "foo",
             "bar",
@@ -171,7 +896,7 @@ mod tests {
             "## Benchmarks",
             "",
             "| Day | Part 1 | Part 2 |",
-            "| :---: | :---: | :---:  |",
+            "| :---: | :---: | :---: |",
             "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` |",
             "| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` |",
             "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` |",
@@ -183,4 +908,264 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn format_configured_columns() {
+        let config = Config {
+            year: Some(2023),
+            readme_columns: vec![Column::Day, Column::Total, Column::Samples, Column::Share],
+            readme_sort: Sort::Cost,
+            ..Config::default()
+        };
+
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(
+            &mut s,
+            get_mock_timings(),
+            190.0,
+            &SourceHashes::new(),
+            &SourceHashes::new(),
+            &config,
+        )
+        .unwrap();
+
+        let lines: Vec<&str> = s.lines().collect();
+        assert_eq!(lines[3], "| Day | Total | Samples | Share |");
+        assert_eq!(lines[4], "| :---: | :---: | :---: | :---: |");
+        assert_eq!(
+            lines[5],
+            "| [Day 4](https://adventofcode.com/2023/day/4) · [source](./src/bin/04.rs) | `90.0ms` | 100 / 100 | `████▊     ` 47.4% |"
+        );
+        assert!(lines[7].starts_with("| [Day 1]"));
+    }
+
+    #[test]
+    fn renders_bars() {
+        assert_eq!(bar(0.0, 4), "    ");
+        assert_eq!(bar(0.5, 4), "██  ");
+        assert_eq!(bar(1.0 / 32.0, 4), "▏   ");
+        assert_eq!(bar(1.0, 4), "████");
+    }
+
+    #[test]
+    fn parses_formatted_durations() {
+        assert_eq!(parse_nanos("74.13ns"), Some(74.13));
+        assert_eq!(parse_nanos("1.5µs"), Some(1500.0));
+        assert_eq!(parse_nanos("20ms"), Some(2e7));
+        assert_eq!(parse_nanos("2s"), Some(2e9));
+        assert_eq!(parse_nanos("-"), None);
+    }
+
+    #[test]
+    fn merges_with_existing_rows() {
+        let config = Config {
+            readme_columns: vec![Column::Day, Column::Part1, Column::Part2, Column::Samples],
+            ..Config::default()
+        };
+
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(
+            &mut s,
+            get_mock_timings(),
+            190.0,
+            &SourceHashes::new(),
+            &SourceHashes::new(),
+            &config,
+        )
+        .unwrap();
+
+        let day_2 = timings(day!(2), Some(timing("1ms", 1e6)), None);
+        update_content(
+            &mut s,
+            vec![day_2],
+            1.0,
+            &SourceHashes::new(),
+            &SourceHashes::new(),
+            &config,
+        )
+        .unwrap();
+
+        let lines: Vec<&str> = s.lines().collect();
+        assert_eq!(
+            lines[5],
+            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` | 100 / 100 |"
+        );
+        assert_eq!(lines[6], "| [Day 2](./src/bin/02.rs) | `1ms` | - | 100 |");
+        assert_eq!(
+            lines[7],
+            "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` | 100 / 100 |"
+        );
+        // without a total column, kept rows contribute the sum of their parts.
+        assert_eq!(lines[9], "**Total: 121.00ms**");
+    }
+
+    #[test]
+    fn marks_stale_rows() {
+        let hashes = |hash: u64| -> SourceHashes {
+            [(day!(1), 1), (day!(2), hash), (day!(4), 4)]
+                .into_iter()
+                .collect()
+        };
+
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(
+            &mut s,
+            get_mock_timings(),
+            190.0,
+            &hashes(2),
+            &hashes(2),
+            &Config::default(),
+        )
+        .unwrap();
+        assert!(s.contains(
+            "<!--- sources: 01=0000000000000001 02=0000000000000002 04=0000000000000004 --->"
+        ));
+
+        let day_1 = get_mock_timings().remove(0);
+        update_content(
+            &mut s,
+            vec![day_1.clone()],
+            30.0,
+            &hashes(3),
+            &hashes(3),
+            &Config::default(),
+        )
+        .unwrap();
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) *(stale)* | `30ms` | `40ms` |"));
+        assert!(s.contains("| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` |"));
+
+        // the row stays stale until the day is measured again.
+        update_content(
+            &mut s,
+            vec![day_1],
+            30.0,
+            &hashes(3),
+            &hashes(3),
+            &Config::default(),
+        )
+        .unwrap();
+        assert!(s.contains("*(stale)*"));
+        update_content(
+            &mut s,
+            get_mock_timings(),
+            190.0,
+            &hashes(3),
+            &hashes(3),
+            &Config::default(),
+        )
+        .unwrap();
+        assert!(!s.contains("*(stale)*"));
+    }
+
+    #[test]
+    fn marks_imported_rows_of_changed_sources_as_stale() {
+        let measured: SourceHashes = [(day!(1), 1), (day!(2), 2), (day!(4), 4)].into();
+        let current: SourceHashes = [(day!(1), 1), (day!(2), 3), (day!(4), 4)].into();
+
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(
+            &mut s,
+            get_mock_timings(),
+            190.0,
+            &measured,
+            &current,
+            &Config::default(),
+        )
+        .unwrap();
+        assert!(s.contains("| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` |"));
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) *(stale)* | `30ms` | `40ms` |"));
+        // the hashes the timings were measured on are recorded, not the current ones.
+        assert!(s.contains("02=0000000000000002"));
+    }
+
+    #[test]
+    fn renders_heap_columns() {
+        let config = Config {
+            readme_columns: vec![Column::Day, Column::Allocated, Column::PeakHeap],
+            ..Config::default()
+        };
+
+        let mut timings = get_mock_timings();
+        timings[0].heap = Some(HeapUsage {
+            total_bytes: 1536,
+            total_blocks: 12,
+            peak_bytes: 100,
+        });
+
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(
+            &mut s,
+            timings,
+            190.0,
+            &SourceHashes::new(),
+            &SourceHashes::new(),
+            &config,
+        )
+        .unwrap();
+        assert!(s.contains("| [Day 1](./src/bin/01.rs) | `1.5 KiB` in 12 blocks | `100 B` |"));
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) | - | - |"));
+
+        // heap usage of kept rows is parsed back from the table.
+        update_content(
+            &mut s,
+            vec![],
+            0.0,
+            &SourceHashes::new(),
+            &SourceHashes::new(),
+            &config,
+        )
+        .unwrap();
+        assert!(s.contains("| [Day 1](./src/bin/01.rs) | `1.5 KiB` in 12 blocks | `100 B` |"));
+
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[test]
+    fn renders_cold_columns() {
+        let config = Config {
+            readme_columns: vec![Column::Day, Column::Part1, Column::ColdPart1],
+            ..Config::default()
+        };
+
+        let mut timings = get_mock_timings();
+        timings[0].part_1.as_mut().unwrap().cold_nanos = Some(1.5e7);
+
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(
+            &mut s,
+            timings,
+            190.0,
+            &SourceHashes::new(),
+            &SourceHashes::new(),
+            &config,
+        )
+        .unwrap();
+        assert!(s.contains("| [Day 1](./src/bin/01.rs) | `10ms` | `15.0ms` |"));
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) | `30ms` | - |"));
+
+        // a table with only cold columns keeps its cold runs when rows are merged.
+        let config = Config {
+            readme_columns: vec![Column::Day, Column::ColdPart1],
+            ..config
+        };
+        update_content(
+            &mut s,
+            vec![],
+            0.0,
+            &SourceHashes::new(),
+            &SourceHashes::new(),
+            &config,
+        )
+        .unwrap();
+        update_content(
+            &mut s,
+            vec![],
+            0.0,
+            &SourceHashes::new(),
+            &SourceHashes::new(),
+            &config,
+        )
+        .unwrap();
+        assert!(s.contains("| [Day 1](./src/bin/01.rs) | `15.0ms` |"));
+    }
 }
@@ -0,0 +1,470 @@
+/// Module that renders benchmark timings into a self-contained HTML report with inline SVG charts.
+/// Every report records its timings in a history file in the report directory, which is used to draw sparklines.
+use std::{
+    fmt::Write,
+    fs, io,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::template::{
+    config::Config,
+    readme_benchmarks::{SourceHashes, Step, Timing, Timings},
+    Day,
+};
+
+const HISTORY_FILE: &str = "history.csv";
+const HISTORY_HEADER: &str = "timestamp,day,part_1_nanos,part_2_nanos,total_nanos,source_hash";
+const REPORT_FILE: &str = "index.html";
+
+const CHART_WIDTH: f64 = 720.0;
+const LABEL_WIDTH: f64 = 60.0;
+const ROW_HEIGHT: f64 = 22.0;
+const SPARKLINE_WIDTH: f64 = 120.0;
+const SPARKLINE_HEIGHT: f64 = 20.0;
+
+const COLOR_PARSE: &str = "#9999cc";
+const COLOR_PART_1: &str = "#009900";
+const COLOR_PART_2: &str = "#ffff66";
+/// Colors of the steps in the bar chart, in the order of [`Step::ALL`].
+const STEP_COLORS: [&str; 3] = [COLOR_PARSE, COLOR_PART_1, COLOR_PART_2];
+
+#[derive(Debug)]
+pub enum Error {
+    Parser(String),
+    IO(io::Error),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+/// A previous measurement of a day, read from the history file.
+#[derive(Clone, Debug, PartialEq)]
+struct HistoryEntry {
+    timestamp: u64,
+    day: Day,
+    part_1_nanos: Option<f64>,
+    part_2_nanos: Option<f64>,
+    total_nanos: f64,
+    /// Hash of the day's source file when it was measured.
+    source_hash: Option<u64>,
+}
+
+/// Writes `index.html` to `dir` and records the timings in the report history.
+/// The history keeps one entry per day and source hash, so importing the same timings twice does not add entries.
+pub fn write(
+    dir: &Path,
+    timings: &[Timings],
+    source_hashes: &SourceHashes,
+    config: &Config,
+) -> Result<(), Error> {
+    fs::create_dir_all(dir)?;
+
+    let history_path = dir.join(HISTORY_FILE);
+    let mut history = match fs::read_to_string(&history_path) {
+        Ok(s) => parse_history(&s)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => vec![],
+        Err(e) => return Err(e.into()),
+    };
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+
+    record_history(&mut history, timestamp, timings, source_hashes);
+
+    fs::write(&history_path, format_history(&history))?;
+    fs::write(dir.join(REPORT_FILE), render(timings, &history, config))?;
+
+    Ok(())
+}
+
+fn parse_history(s: &str) -> Result<Vec<HistoryEntry>, Error> {
+    fn optional(s: &str) -> Option<&str> {
+        (!s.is_empty()).then_some(s)
+    }
+
+    s.lines()
+        .skip(1)
+        .filter(|l| !l.trim().is_empty())
+        .map(|line| {
+            let fields: Vec<&str> = line.split(',').collect();
+            let parse_error = || Error::Parser(format!("invalid history record: {line}"));
+
+            // histories written before source hashes were recorded have no `source_hash` column.
+            let (timestamp, day, part_1, part_2, total_nanos, source_hash) = match fields[..] {
+                [timestamp, day, part_1, part_2, total_nanos] => {
+                    (timestamp, day, part_1, part_2, total_nanos, None)
+                }
+                [timestamp, day, part_1, part_2, total_nanos, source_hash] => (
+                    timestamp,
+                    day,
+                    part_1,
+                    part_2,
+                    total_nanos,
+                    optional(source_hash),
+                ),
+                _ => return Err(parse_error()),
+            };
+
+            let nanos = |s: &str| optional(s).map(str::parse).transpose();
+
+            Ok(HistoryEntry {
+                timestamp: timestamp.parse().map_err(|_| parse_error())?,
+                day: day.parse().map_err(|_| parse_error())?,
+                part_1_nanos: nanos(part_1).map_err(|_| parse_error())?,
+                part_2_nanos: nanos(part_2).map_err(|_| parse_error())?,
+                total_nanos: total_nanos.parse().map_err(|_| parse_error())?,
+                source_hash: source_hash
+                    .map(|h| u64::from_str_radix(h, 16))
+                    .transpose()
+                    .map_err(|_| parse_error())?,
+            })
+        })
+        .collect()
+}
+
+/// Adds the timings to the history. An entry with the same day and source hash is replaced,
+/// as it measured the same code.
+fn record_history(
+    history: &mut Vec<HistoryEntry>,
+    timestamp: u64,
+    timings: &[Timings],
+    source_hashes: &SourceHashes,
+) {
+    let nanos = |timing: &Option<Timing>| timing.as_ref().map(|t| t.nanos);
+
+    for t in timings {
+        let source_hash = source_hashes.get(&t.day).copied();
+        if source_hash.is_some() {
+            history.retain(|h| h.day != t.day || h.source_hash != source_hash);
+        }
+
+        history.push(HistoryEntry {
+            timestamp,
+            day: t.day,
+            part_1_nanos: nanos(&t.part_1),
+            part_2_nanos: nanos(&t.part_2),
+            total_nanos: t.total_nanos,
+            source_hash,
+        });
+    }
+}
+
+fn format_history(history: &[HistoryEntry]) -> String {
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    let mut s = format!("{HISTORY_HEADER}\n");
+
+    for h in history {
+        let _ = writeln!(
+            s,
+            "{},{},{},{},{},{}",
+            h.timestamp,
+            h.day,
+            optional(h.part_1_nanos.map(|n| n.to_string())),
+            optional(h.part_2_nanos.map(|n| n.to_string())),
+            h.total_nanos,
+            optional(h.source_hash.map(|h| format!("{h:016x}")))
+        );
+    }
+
+    s
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn render(timings: &[Timings], history: &[HistoryEntry], config: &Config) -> String {
+    let total_nanos: f64 = timings.iter().map(|t| t.total_nanos).sum();
+    let title = match config.year {
+        Some(year) => format!("Advent of Code {year} Benchmarks"),
+        None => "Advent of Code Benchmarks".into(),
+    };
+
+    let mut rows = String::new();
+    for t in timings {
+        let cell =
+            |timing: &Option<Timing>| timing.as_ref().map_or("-".into(), |t| escape(&t.formatted));
+
+        let day_history: Vec<f64> = history
+            .iter()
+            .filter(|h| h.day == t.day)
+            .map(|h| h.total_nanos)
+            .collect();
+
+        let _ = writeln!(
+            rows,
+            "<tr><td>Day {}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            t.day.into_inner(),
+            cell(&t.part_1),
+            cell(&t.part_2),
+            escape(&format_millis(t.total_nanos)),
+            sparkline(&day_history)
+        );
+    }
+
+    let runs = {
+        let mut timestamps: Vec<u64> = history.iter().map(|h| h.timestamp).collect();
+        timestamps.dedup();
+        timestamps.len()
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; background: #0f0f23; color: #cccccc; margin: 2em; }}
+h1 {{ color: #00cc00; }}
+table {{ border-collapse: collapse; margin-top: 1em; }}
+td, th {{ padding: 0.2em 1em; text-align: right; border-bottom: 1px solid #333340; }}
+svg text {{ fill: #cccccc; font-size: 12px; }}
+.legend span {{ display: inline-block; width: 1em; height: 1em; vertical-align: middle; margin: 0 0.3em 0 1em; }}
+#log-scale:checked ~ .linear, #log-scale:not(:checked) ~ .log {{ display: none; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p><strong>Total: {}</strong> across {} days, {runs} recorded run(s).</p>
+<p class="legend"><span style="background: {COLOR_PARSE}"></span>Parse<span style="background: {COLOR_PART_1}"></span>Part 1<span style="background: {COLOR_PART_2}"></span>Part 2</p>
+<input type="checkbox" id="log-scale"><label for="log-scale"> Logarithmic scale</label>
+<div class="linear">
+{}
+</div>
+<div class="log">
+{}
+</div>
+<table>
+<tr><th>Day</th><th>Part 1</th><th>Part 2</th><th>Total</th><th>History</th></tr>
+{rows}</table>
+</body>
+</html>
+"#,
+        escape(&format_millis(total_nanos)),
+        timings.len(),
+        bar_chart(timings, false),
+        bar_chart(timings, true),
+    )
+}
+
+/// Renders a horizontal bar per day, stacking the parse step, part 1 and part 2.
+/// With a logarithmic scale, the length of a bar is the logarithm of the day's total, split by the steps' share.
+fn bar_chart(timings: &[Timings], log_scale: bool) -> String {
+    let step_nanos = |t: &Timings| Step::ALL.map(|step| t.step(step).map_or(0.0, |s| s.nanos));
+
+    // shift by one nanosecond so that sub-nanosecond totals do not produce negative lengths.
+    let scale = |nanos: f64| {
+        if log_scale {
+            (nanos + 1.0).log10()
+        } else {
+            nanos
+        }
+    };
+
+    let max = timings
+        .iter()
+        .map(|t| scale(step_nanos(t).iter().sum()))
+        .fold(0.0, f64::max);
+
+    #[allow(clippy::cast_precision_loss)]
+    let height = timings.len() as f64 * ROW_HEIGHT;
+    let bar_width = CHART_WIDTH - LABEL_WIDTH;
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{CHART_WIDTH}" height="{height}" viewBox="0 0 {CHART_WIDTH} {height}">"#
+    );
+
+    for (i, t) in timings.iter().enumerate() {
+        #[allow(clippy::cast_precision_loss)]
+        let y = i as f64 * ROW_HEIGHT;
+        let nanos = step_nanos(t);
+        let sum: f64 = nanos.iter().sum();
+
+        let total_width = if max > 0.0 {
+            scale(sum) / max * bar_width
+        } else {
+            0.0
+        };
+
+        let _ = write!(
+            svg,
+            r#"<text x="0" y="{:.1}">Day {}</text>"#,
+            y + ROW_HEIGHT * 0.7,
+            t.day.into_inner(),
+        );
+
+        let mut x = LABEL_WIDTH;
+        for ((step, nanos), color) in Step::ALL.iter().zip(nanos).zip(STEP_COLORS) {
+            if nanos <= 0.0 {
+                continue;
+            }
+
+            let width = total_width * nanos / sum;
+            let _ = write!(
+                svg,
+                r#"<rect x="{x:.1}" y="{:.1}" width="{width:.1}" height="{:.1}" fill="{color}"><title>{step}</title></rect>"#,
+                y + 3.0,
+                ROW_HEIGHT - 6.0,
+            );
+            x += width;
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Renders a small line chart of previous totals. Returns `-` if there are less than two data points.
+fn sparkline(values: &[f64]) -> String {
+    if values.len() < 2 {
+        return "-".into();
+    }
+
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(0.0, f64::max);
+    let range = if max > min { max - min } else { 1.0 };
+
+    #[allow(clippy::cast_precision_loss)]
+    let step = SPARKLINE_WIDTH / (values.len() - 1) as f64;
+
+    let points: Vec<String> = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            #[allow(clippy::cast_precision_loss)]
+            let x = i as f64 * step;
+            let y = SPARKLINE_HEIGHT - 2.0 - (v - min) / range * (SPARKLINE_HEIGHT - 4.0);
+            format!("{x:.1},{y:.1}")
+        })
+        .collect();
+
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{SPARKLINE_WIDTH}" height="{SPARKLINE_HEIGHT}"><polyline points="{}" fill="none" stroke="{COLOR_PART_1}" stroke-width="1.5"/></svg>"#,
+        points.join(" ")
+    )
+}
+
+fn format_millis(nanos: f64) -> String {
+    format!("{:.2}ms", nanos / 1_000_000_f64)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        bar_chart, format_history, parse_history, record_history, render, sparkline, HistoryEntry,
+    };
+    use crate::{
+        day,
+        template::{
+            config::Config,
+            readme_benchmarks::{
+                fixtures::{get_mock_timings, timing},
+                SourceHashes, Timings,
+            },
+        },
+    };
+
+    #[test]
+    fn parses_history() {
+        let history = parse_history(
+            "timestamp,day,part_1_nanos,part_2_nanos,total_nanos,source_hash\n1,01,10,20,30,00000000000000ff\n1,02,5,,5,\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            history,
+            vec![
+                HistoryEntry {
+                    timestamp: 1,
+                    day: day!(1),
+                    part_1_nanos: Some(10.0),
+                    part_2_nanos: Some(20.0),
+                    total_nanos: 30.0,
+                    source_hash: Some(0xff),
+                },
+                HistoryEntry {
+                    timestamp: 1,
+                    day: day!(2),
+                    part_1_nanos: Some(5.0),
+                    part_2_nanos: None,
+                    total_nanos: 5.0,
+                    source_hash: None,
+                }
+            ]
+        );
+        assert_eq!(parse_history(&format_history(&history)).unwrap(), history);
+        assert!(parse_history("header\n1,01").is_err());
+    }
+
+    #[test]
+    fn parses_history_without_source_hashes() {
+        let history =
+            parse_history("timestamp,day,part_1_nanos,part_2_nanos,total_nanos\n1,01,10,20,30\n")
+                .unwrap();
+        assert_eq!(history[0].source_hash, None);
+        assert_eq!(history[0].total_nanos, 30.0);
+    }
+
+    #[test]
+    fn replaces_history_of_the_same_source() {
+        let hashes = |hash: u64| -> SourceHashes { [(day!(1), hash), (day!(2), hash)].into() };
+        let timings = &get_mock_timings()[..2];
+
+        let mut history = vec![];
+        record_history(&mut history, 1, timings, &hashes(1));
+        record_history(&mut history, 2, timings, &hashes(1));
+        record_history(&mut history, 3, &timings[..1], &hashes(2));
+
+        let entries: Vec<_> = history.iter().map(|h| (h.timestamp, h.day)).collect();
+        assert_eq!(entries, [(2, day!(1)), (2, day!(2)), (3, day!(1))]);
+    }
+
+    #[test]
+    fn scales_stacked_bars() {
+        let svg = bar_chart(&get_mock_timings(), false);
+        // the slowest day spans the full chart, its first part four ninths of it.
+        assert!(svg.contains(r##"width="293.3" height="16.0" fill="#009900""##));
+        assert!(svg.contains(r##"width="366.7" height="16.0" fill="#ffff66""##));
+        assert!(!svg.contains("<title>Parse</title>"));
+    }
+
+    #[test]
+    fn charts_parse_step() {
+        let timings = vec![Timings {
+            parse: Some(timing("10ms", 1e7)),
+            total_nanos: 4e7,
+            ..get_mock_timings()[0].clone()
+        }];
+
+        let svg = bar_chart(&timings, false);
+        // parsing takes a quarter of the day, part 1 another quarter.
+        assert!(svg.contains(
+            r##"x="60.0" y="3.0" width="165.0" height="16.0" fill="#9999cc"><title>Parse</title>"##
+        ));
+        assert!(svg.contains(r##"x="225.0" y="3.0" width="165.0" height="16.0" fill="#009900""##));
+    }
+
+    #[test]
+    fn renders_sparklines() {
+        assert_eq!(sparkline(&[1.0]), "-");
+        assert!(sparkline(&[1.0, 2.0]).contains(r#"points="0.0,18.0 120.0,2.0""#));
+    }
+
+    #[test]
+    fn renders_report() {
+        let html = render(&get_mock_timings(), &[], &Config::default());
+        assert!(html.contains("<td>Day 1</td><td>10ms</td><td>20ms</td><td>30.00ms</td>"));
+        assert!(html.contains("Total: 190.00ms"));
+    }
+}
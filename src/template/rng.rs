@@ -0,0 +1,63 @@
+//! Module with the random number generator that input generators receive, both in differential tests and for scaled inputs.
+//! It is seeded, so that generated inputs can be reproduced.
+
+/// Default seed of generated inputs, fixed so that they are the same on every run.
+pub const SEED: u64 = 0x5eed;
+
+/// A small, seedable random number generator (SplitMix64).
+pub struct Rng(u64);
+
+impl Rng {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `low..high`. `high` must be greater than `low`.
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        assert!(low < high, "empty range {low}..{high}");
+        low + self.next_u64() % (high - low)
+    }
+
+    /// Returns `true` with the given probability.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        #[allow(clippy::cast_precision_loss)]
+        let sample = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        sample < probability
+    }
+
+    /// Returns a random element of the slice, which must not be empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        #[allow(clippy::cast_possible_truncation)]
+        let index = self.range(0, items.len() as u64) as usize;
+        &items[index]
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Rng;
+
+    #[test]
+    fn draws_reproducible_numbers() {
+        let (mut a, mut b) = (Rng::new(42), Rng::new(42));
+        assert!((0..100).all(|_| a.next_u64() == b.next_u64()));
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+
+    #[test]
+    fn stays_within_bounds() {
+        let mut rng = Rng::new(7);
+        assert!((0..100).all(|_| (3..5).contains(&rng.range(3, 5))));
+        assert!((0..100).all(|_| [1, 2, 3].contains(rng.choose(&[1, 2, 3]))));
+        assert!((0..100).all(|_| !rng.chance(0.0) && rng.chance(1.0)));
+    }
+}
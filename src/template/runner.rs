@@ -1,70 +1,339 @@
 /// Encapsulates code that interacts with solution functions.
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{
+    self, aoc_cli,
+    config::{self, BenchSettings},
+    log, ocr,
+    readme_benchmarks::{HeapUsage, Step},
+    scaling,
+    solution::{IntoOutcome, Outcome},
+    variants::{self, Comparison, Variant},
+    visualize, Day, Solution, ANSI_ITALIC, ANSI_RED, ANSI_RESET,
+};
 use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{stdout, Write};
+use std::panic::{self, AssertUnwindSafe, PanicHookInfo};
 use std::process::Output;
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
 use super::ANSI_BOLD;
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// Set by commands that run solutions as child processes and parse their timings, e.g. `all`.
+/// The runner then reports the exact durations of each step on a separate line, see [`print_raw_timing`].
+pub const RAW_TIMINGS_VAR: &str = "AOC_RAW_TIMINGS";
+
+/// Runs a [`Solution`]: the input is parsed once, timed as a separate step, and then shared by both parts.
+pub fn run_solution<S: Solution>(input: &str, day: Day) {
+    if let Some(title) = S::title() {
+        println!("{ANSI_BOLD}{title}{ANSI_RESET}");
+    }
+
+    // the parts cannot run without their input.
+    let Some(parsed) = run_parse(S::parse, input, day) else {
+        return;
+    };
+
+    run_part(|input: &S::Input| S::part_one(input), &parsed, day, 1);
+    run_part(|input: &S::Input| S::part_two(input), &parsed, day, 2);
+}
+
+/// Runs the parse step. A panic is caught and reported like a panicking part, `None` is returned then.
+fn run_parse<'a, T>(func: impl Fn(&'a str) -> T, input: &'a str, day: Day) -> Option<T> {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(record_panic));
+
+    let (parsed, cold, duration, deviation, samples, heap) = run_timed(
+        |input| panic::catch_unwind(AssertUnwindSafe(|| func(input))),
+        input,
+        |parsed| {
+            if parsed.is_ok() {
+                print!("Parse: ✔");
+            }
+            parsed.is_ok()
+        },
+        day,
+        Step::Parse,
+    );
+
+    panic::set_hook(default_hook);
+
+    let Ok(parsed) = parsed else {
+        println!("Parse: {ANSI_RED}✖ panicked: {}{ANSI_RESET}", take_panic());
+        return None;
+    };
+
+    println!(
+        "\rParse: ✔{}",
+        format_duration(&cold, &duration, &deviation, samples)
+    );
+    print_raw_timing("Parse", &cold, &duration, &deviation);
+
+    if let Some(heap) = heap {
+        println!("Parse heap: {heap}");
+    }
+
+    Some(parsed)
+}
+
+/// Runs a part that returns either `Option<T>` or `Result<T, E>`.
+/// Panics are caught, so the next part still runs. Failed and panicked parts are not benchmarked.
+pub fn run_part<I: Clone, R: IntoOutcome>(func: impl Fn(I) -> R, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(record_panic));
+    visualize::start_part(part);
+
+    let (result, cold, duration, deviation, samples, heap) = run_timed(
+        |input| catch_panic(|| func(input)),
+        input.clone(),
+        |result| {
+            print_result(result, &part_str, "");
+            !matches!(result, Outcome::Failed(_) | Outcome::Panicked(_))
+        },
+        day,
+        Step::Part(part),
+    );
+
+    panic::set_hook(default_hook);
+
+    print_result(
+        &result,
+        &part_str,
+        &format_duration(&cold, &duration, &deviation, samples),
+    );
+    print_raw_timing(&part_str, &cold, &duration, &deviation);
+
+    if let Some(heap) = heap {
+        println!("{part_str} heap: {heap}");
+    }
+
+    #[cfg(feature = "cpu-profile")]
+    if !matches!(result, Outcome::Failed(_) | Outcome::Panicked(_)) {
+        profile_part(&func, &input, day, part);
+    }
+
+    if let Outcome::Solved(result) = result {
+        submit_result(ocr::answer(&result.to_string()), day, part);
+    }
+}
+
+/// Runs all variants of each part, benchmarks the ones that solve it and prints how they compare to the first variant.
+/// Exits with an error if any variant's outcome differs from the first's.
+pub fn compare_variants(variants: impl IntoIterator<Item = Variant>, input: &str) {
+    let mut variants: Vec<Variant> = variants.into_iter().collect();
+    // stable, so the defaults stay the reference of each part.
+    variants.sort_by_key(|variant| variant.part);
+
+    println!(
+        "{ANSI_ITALIC}Comparing variants, benchmarking with {}.{ANSI_RESET}",
+        bench_settings()
+    );
+
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(record_panic));
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    let mut agree = true;
+
+    for part_variants in variants.chunk_by(|a, b| a.part == b.part) {
+        let part = part_variants[0].part;
+        let mut comparisons = vec![];
+
+        for variant in part_variants {
+            let label = format!("Part {part} {}", variant.name);
+            print!("{label}");
+            let _ = stdout().flush();
+
+            let outcome = catch_panic(|| variant.run(input));
+            let timing = matches!(outcome, Outcome::Solved(_)).then(|| {
+                let (mean, deviation, _) = bench(|input| catch_panic(|| variant.run(input)), input);
+                (mean, deviation)
+            });
+            // the table replaces the progress line.
+            print!("\r{:width$}\r", "", width = label.chars().count() + 12);
+
+            comparisons.push(Comparison {
+                name: variant.name,
+                outcome,
+                timing,
+            });
+        }
+
+        println!("\n{ANSI_BOLD}Part {part}{ANSI_RESET}");
+        for line in variants::format_table(&comparisons) {
+            println!("{line}");
+        }
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+        let disagreeing = variants::disagreeing(&comparisons);
+        if !disagreeing.is_empty() {
+            agree = false;
+            println!(
+                "{ANSI_RED}✖ Results differ from {}: {}.{ANSI_RESET}",
+                comparisons[0].name,
+                disagreeing.join(", ")
+            );
+        }
+    }
+
+    panic::set_hook(default_hook);
+
+    if !agree {
+        process::exit(1);
+    }
+}
+
+/// Message and location of the last panic, recorded by the panic hook that [`run_part`] and [`run_parse`] install.
+static LAST_PANIC: Mutex<Option<String>> = Mutex::new(None);
+
+/// Records the panic instead of printing it, it is reported in the `Part N:` or `Parse:` line.
+fn record_panic(info: &PanicHookInfo) {
+    let payload = info.payload();
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>");
+
+    let panic = match info.location() {
+        Some(location) => format!("{message} at {location}"),
+        None => message.to_string(),
+    };
+
+    if let Ok(mut last_panic) = LAST_PANIC.lock() {
+        *last_panic = Some(panic);
     }
 }
 
+/// Runs a part, turning a panic into [`Outcome::Panicked`].
+fn catch_panic<R: IntoOutcome>(func: impl FnOnce() -> R) -> Outcome<R::Answer> {
+    match panic::catch_unwind(AssertUnwindSafe(func)) {
+        Ok(result) => result.into_outcome(),
+        Err(_) => Outcome::Panicked(take_panic()),
+    }
+}
+
+/// Returns the panic that [`record_panic`] recorded last.
+fn take_panic() -> String {
+    LAST_PANIC
+        .lock()
+        .ok()
+        .and_then(|mut last_panic| last_panic.take())
+        .unwrap_or_else(|| "unknown panic".into())
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
-///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+///  2. in release, the function is benched (approx. the configured bench time (default: 1 second), within the configured sample bounds.)
+///     The number of samples is estimated from warmup runs.
+///
+/// The duration of the first, cold execution is returned alongside the warm statistics of the bench.
+/// If the `dhat-heap` feature is enabled, the heap usage of the first execution is returned as well,
+/// and its profile is written to a file per day and step, see [`heap_profiler`].
+///
+/// The hook receives the result of the first execution and returns whether the function should be benched.
+#[cfg_attr(not(feature = "dhat-heap"), allow(unused_variables))]
 fn run_timed<I: Clone, T>(
     func: impl Fn(I) -> T,
     input: I,
-    hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+    hook: impl Fn(&T) -> bool,
+    day: Day,
+    step: Step,
+) -> (T, Duration, Duration, Duration, u128, Option<HeapUsage>) {
     let timer = Instant::now();
-    let result = {
+    let (result, heap) = {
         let input = input.clone();
 
         #[cfg(feature = "dhat-heap")]
-        let _profiler = dhat::Profiler::new_heap();
+        let _profiler = heap_profiler(day, step);
+
+        let result = func(input);
+
+        #[cfg(feature = "dhat-heap")]
+        let heap = {
+            let stats = dhat::HeapStats::get();
+            Some(HeapUsage {
+                total_bytes: stats.total_bytes,
+                total_blocks: stats.total_blocks,
+                peak_bytes: stats.max_bytes as u64,
+            })
+        };
+
+        #[cfg(not(feature = "dhat-heap"))]
+        let heap = None;
 
-        func(input)
+        (result, heap)
     };
     let base_time = timer.elapsed();
 
-    hook(&result);
+    // benchmarks would draw every frame again.
+    let should_bench = hook(&result) && !visualize::enabled();
 
-    let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
+    let run = if should_bench && std::env::args().any(|x| x == "--time") {
+        bench(func, input)
     } else {
-        (base_time, 1)
+        (base_time, Duration::ZERO, 1)
     };
 
-    (result, run.0, run.1)
+    (result, base_time, run.0, run.1, run.2, heap)
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
+/// Starts profiling the heap. The profile is written when the profiler is dropped,
+/// to `dhat-heap-<day>-<part>.json`, or `dhat-heap-<day>-parse.json` for the parse step.
+#[cfg(feature = "dhat-heap")]
+fn heap_profiler(day: Day, step: Step) -> dhat::Profiler {
+    let step = match step {
+        Step::Parse => "parse".to_string(),
+        Step::Part(part) => part.to_string(),
+    };
+
+    dhat::Profiler::builder()
+        .file_name(format!("dhat-heap-{day}-{step}.json"))
+        .build()
+}
+
+/// Profiles the part for the configured bench time and prints where the profile was written to.
+#[cfg(feature = "cpu-profile")]
+fn profile_part<I: Clone, T>(func: impl Fn(I) -> T, input: &I, day: Day, part: u8) {
+    let duration = bench_settings().time;
+    print!(" > {ANSI_ITALIC}profiling for {duration:?}{ANSI_RESET}");
+    let _ = stdout().flush();
+
+    log::silence(true);
+    let profile = template::profiler::profile(func, input, day, part, duration);
+    log::silence(false);
+
+    match profile {
+        Ok(profile) => println!(
+            "\rPart {part} profile: {} samples of {} runs, written to {} and {}",
+            profile.samples,
+            profile.runs,
+            profile.flamegraph.display(),
+            profile.folded.display()
+        ),
+        Err(e) => {
+            println!();
+            eprintln!("Failed to profile part {part}: {e:?}");
+        }
+    }
+}
+
+/// Returns the mean and standard deviation of the samples, along with the number of samples.
+fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I) -> (Duration, Duration, u128) {
     let mut stdout = stdout();
+    let settings = bench_settings();
 
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
     let _ = stdout.flush();
 
-    let bench_iterations = cmp::min(
-        10000,
-        cmp::max(
-            Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10),
-            10,
-        ),
-    );
+    // log messages would be printed for every sample and distort the timings.
+    log::silence(true);
+
+    let run_time = warmup(&func, &input, settings.warmup);
+
+    let bench_iterations = (settings.time.as_nanos() / cmp::max(run_time.as_nanos(), 10))
+        .clamp(settings.min_samples, settings.max_samples);
 
     let mut timers: Vec<Duration> = vec![];
 
@@ -76,13 +345,75 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
         timers.push(timer.elapsed());
     }
 
+    log::silence(false);
+
+    let average = average_duration(&timers);
+
     (
         #[allow(clippy::cast_possible_truncation)]
-        Duration::from_nanos(average_duration(&timers) as u64),
+        Duration::from_nanos(average as u64),
+        deviation(&timers, average),
         bench_iterations,
     )
 }
 
+/// Runs the function repeatedly for at least the warmup time, at least once.
+/// Returns the average time of a warmup run, which calibrates the number of samples.
+fn warmup<I: Clone, T>(func: &impl Fn(I) -> T, input: &I, warmup: Duration) -> Duration {
+    let timer = Instant::now();
+    let mut runs: u32 = 0;
+
+    while runs == 0 || timer.elapsed() < warmup {
+        black_box(func(black_box(input.clone())));
+        runs += 1;
+    }
+
+    timer.elapsed() / runs
+}
+
+/// Returns the configured benchmark settings, overridden by the arguments the binary was invoked with.
+#[must_use]
+pub fn bench_settings() -> BenchSettings {
+    config::get()
+        .bench
+        .with_args(&mut pico_args::Arguments::from_env())
+        .unwrap_or_else(|e| {
+            eprintln!("Invalid benchmark settings: {e}");
+            process::exit(1);
+        })
+}
+
+/// Sizes the global rayon thread pool if the binary was invoked with `--threads <count>`.
+pub fn configure_threads() {
+    let threads = pico_args::Arguments::from_env()
+        .opt_value_from_fn("--threads", scaling::parse_threads)
+        .unwrap_or_else(|e| {
+            eprintln!("Invalid thread count: {e}");
+            process::exit(1);
+        });
+
+    if let Some(threads) = threads {
+        if let Err(e) = scaling::configure_pool(threads) {
+            eprintln!("Failed to configure the thread pool: {e}");
+            process::exit(1);
+        }
+        println!(
+            "{ANSI_ITALIC}Running with {}.{ANSI_RESET}",
+            scaling::format_threads(threads)
+        );
+    }
+}
+
+/// Prints the benchmark settings if the binary was invoked with `--time`.
+pub fn print_bench_settings() {
+    if env::args().any(|x| x == "--time") {
+        println!(
+            "{ANSI_ITALIC}Benchmarking with {}.{ANSI_RESET}",
+            bench_settings()
+        );
+    }
+}
+
 fn average_duration(numbers: &[Duration]) -> u128 {
     numbers
         .iter()
@@ -91,21 +422,60 @@ fn average_duration(numbers: &[Duration]) -> u128 {
         / numbers.len() as u128
 }
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
+/// Computes the sample standard deviation of the durations.
+fn deviation(numbers: &[Duration], average: u128) -> Duration {
+    if numbers.len() < 2 {
+        return Duration::ZERO;
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let variance = numbers
+        .iter()
+        .map(|d| (d.as_nanos() as f64 - average as f64).powi(2))
+        .sum::<f64>()
+        / (numbers.len() - 1) as f64;
+
+    Duration::from_secs_f64(variance.sqrt() / 1_000_000_000_f64)
+}
+
+fn format_duration(
+    cold: &Duration,
+    duration: &Duration,
+    deviation: &Duration,
+    samples: u128,
+) -> String {
     if samples == 1 {
         format!(" ({duration:.1?})")
     } else {
-        format!(" ({duration:.1?} @ {samples} samples)")
+        format!(" (cold {cold:.1?}, warm {duration:.1?} ± {deviation:.1?} @ {samples} samples)")
+    }
+}
+
+/// Prints the durations in nanoseconds if a parent process asked for them, e.g. `Part 1 nanos: 74130 1200 1500000`.
+/// The formatted durations are rounded, so the parent would lose precision when parsing them instead.
+fn print_raw_timing(label: &str, cold: &Duration, duration: &Duration, deviation: &Duration) {
+    if env::var_os(RAW_TIMINGS_VAR).is_some() {
+        println!(
+            "{label} nanos: {} {} {}",
+            duration.as_nanos(),
+            deviation.as_nanos(),
+            cold.as_nanos()
+        );
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+fn print_result<T: Display>(result: &Outcome<T>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
     match result {
-        Some(result) => {
-            if result.to_string().contains('\n') {
-                let str = format!("{part}: ▼ {duration_str}");
+        Outcome::Solved(result) => {
+            let result = result.to_string();
+            if result.contains('\n') {
+                // letters drawn as ASCII art are shown as text next to the art.
+                let str = match ocr::recognize(&result) {
+                    Some(text) => format!("{part}: ▼ {ANSI_BOLD}{text}{ANSI_RESET}{duration_str}"),
+                    None => format!("{part}: ▼ {duration_str}"),
+                };
                 if is_intermediate_result {
                     print!("{str}");
                 } else {
@@ -123,12 +493,23 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 }
             }
         }
-        None => {
+        Outcome::NotImplemented => {
             if is_intermediate_result {
-                print!("{part}: ✖");
+                print!("{part}: ✖ not implemented");
             } else {
                 print!("\r");
-                println!("{part}: ✖             ");
+                println!("{part}: ✖ not implemented             ");
+            }
+        }
+        Outcome::Failed(error) => {
+            // failures are not benched, so the intermediate result is final.
+            if !is_intermediate_result {
+                println!("{part}: {ANSI_RED}✖ failed: {error}{ANSI_RESET}");
+            }
+        }
+        Outcome::Panicked(panic) => {
+            if !is_intermediate_result {
+                println!("{part}: {ANSI_RED}✖ panicked: {panic}{ANSI_RESET}");
             }
         }
     }
@@ -137,6 +518,7 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
 ///  2. aoc-cli is installed.
+///  3. the solution was run against the default puzzle input.
 fn submit_result<T: Display>(
     result: T,
     day: Day,
@@ -164,11 +546,21 @@ fn submit_result<T: Display>(
         return None;
     }
 
+    if let Some(input) = template::input_arg() {
+        eprintln!("Not submitting: the solution was run against a custom input (\"{input}\").");
+        return None;
+    }
+
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
     }
 
     println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    Some(aoc_cli::submit(
+        day,
+        part,
+        &result.to_string(),
+        config::get(),
+    ))
 }
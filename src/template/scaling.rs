@@ -0,0 +1,161 @@
+/// Module that controls the size of the global rayon thread pool and reports how solutions scale with it.
+///
+/// The global pool can only be configured once per process, so each thread count is benchmarked in a separate run.
+use std::{num::NonZeroUsize, thread};
+
+use crate::template::{
+    readme_benchmarks::{Step, Timings},
+    table, Day, ANSI_BOLD, ANSI_RESET,
+};
+
+/// Benchmark of a day, run with a fixed number of threads.
+pub struct ScalingRun {
+    pub threads: usize,
+    pub timings: Timings,
+}
+
+/// Parses a thread count, which needs to be a positive integer.
+pub fn parse_threads(s: &str) -> Result<usize, String> {
+    match s.parse::<usize>() {
+        Ok(threads) if threads > 0 => Ok(threads),
+        _ => Err(format!(
+            "invalid thread count `{s}`, expecting a positive integer"
+        )),
+    }
+}
+
+/// Sizes the global rayon thread pool. This needs to happen before any parallel iterator is used.
+pub fn configure_pool(threads: usize) -> Result<(), String> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build_global()
+        .map_err(|e| e.to_string())
+}
+
+/// Number of threads the machine can run in parallel.
+#[must_use]
+pub fn available_threads() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// Formats a thread count, e.g. `1 thread` or `4 threads`.
+#[must_use]
+pub fn format_threads(threads: usize) -> String {
+    if threads == 1 {
+        "1 thread".into()
+    } else {
+        format!("{threads} threads")
+    }
+}
+
+/// Thread counts to benchmark: powers of two up to `max`, followed by `max` itself.
+#[must_use]
+pub fn thread_counts(max: usize) -> Vec<usize> {
+    let mut counts: Vec<usize> = (0..)
+        .map(|exp| 1 << exp)
+        .take_while(|&threads| threads < max)
+        .collect();
+    counts.push(max.max(1));
+    counts
+}
+
+/// Formats one line per run, listing each part's time along with its speedup and parallel efficiency
+/// compared to the single-threaded run.
+#[must_use]
+pub fn format_table(runs: &[ScalingRun]) -> Vec<String> {
+    let Some(base) = runs.iter().find(|r| r.threads == 1).or(runs.first()) else {
+        return vec![];
+    };
+
+    let steps: Vec<Step> = Step::ALL
+        .into_iter()
+        .filter(|&step| base.timings.step(step).is_some())
+        .collect();
+
+    let mut rows = vec![std::iter::once("Threads".to_string())
+        .chain(steps.iter().map(Step::to_string))
+        .collect::<Vec<_>>()];
+
+    for run in runs {
+        let mut row = vec![run.threads.to_string()];
+
+        for &step in &steps {
+            row.push(match (base.timings.step(step), run.timings.step(step)) {
+                (Some(base_timing), Some(timing)) if timing.nanos > 0.0 => {
+                    let speedup = base_timing.nanos / timing.nanos;
+                    #[allow(clippy::cast_precision_loss)]
+                    let efficiency = speedup * base.threads as f64 / run.threads as f64;
+                    format!(
+                        "{} ({speedup:.2}×, {:.0}%)",
+                        timing.formatted,
+                        efficiency * 100.0
+                    )
+                }
+                (_, Some(timing)) => timing.formatted.clone(),
+                (_, None) => "-".into(),
+            });
+        }
+
+        rows.push(row);
+    }
+
+    table::format(&rows)
+}
+
+pub fn print(day: Day, runs: &[ScalingRun]) {
+    println!(
+        "\n{ANSI_BOLD}Scaling of day {day}{ANSI_RESET} (speedup, parallel efficiency compared to 1 thread):"
+    );
+
+    for line in format_table(runs) {
+        println!("{line}");
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_table, parse_threads, thread_counts, ScalingRun};
+    use crate::{
+        day,
+        template::readme_benchmarks::fixtures::{timing, timings},
+    };
+
+    fn run(threads: usize, part_1: f64) -> ScalingRun {
+        ScalingRun {
+            threads,
+            timings: timings(
+                day!(5),
+                Some(timing(&format!("{part_1}ms"), part_1 * 1_000_000.0)),
+                None,
+            ),
+        }
+    }
+
+    #[test]
+    fn parses_thread_counts() {
+        assert_eq!(parse_threads("4"), Ok(4));
+        assert!(parse_threads("0").is_err());
+        assert!(parse_threads("all").is_err());
+    }
+
+    #[test]
+    fn doubles_thread_counts() {
+        assert_eq!(thread_counts(1), [1]);
+        assert_eq!(thread_counts(6), [1, 2, 4, 6]);
+        assert_eq!(thread_counts(8), [1, 2, 4, 8]);
+    }
+
+    #[test]
+    fn reports_speedup_and_efficiency() {
+        let table = format_table(&[run(1, 8.0), run(2, 4.0), run(4, 4.0)]);
+        assert_eq!(
+            table,
+            [
+                "Threads  Part 1",
+                "1        8ms (1.00×, 100%)",
+                "2        4ms (2.00×, 100%)",
+                "4        4ms (2.00×, 50%)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,195 @@
+/// The [`Solution`] trait, an alternative to free `part_one` and `part_two` functions,
+/// and the [`Outcome`] of a part, which both kinds of solutions report.
+///
+/// The input is parsed once and shared by both parts, and parsing is timed as a separate step.
+use std::{error::Error, fmt::Display};
+
+use crate::template::{read_file, Day};
+
+/// What running a part produced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome<T> {
+    Solved(T),
+    /// The part returned `None`, it is not implemented yet.
+    NotImplemented,
+    /// The part returned an error, formatted along with its chain of causes.
+    Failed(String),
+    /// The part panicked, with the panic message and its location.
+    Panicked(String),
+}
+
+/// Return types of parts: `Option<T>`, where `None` means that the part is not implemented,
+/// and `Result<T, E>`, where an error means that the part failed.
+/// Errors can be anything that converts into a boxed [`Error`], e.g. `String`, `anyhow::Error` or any type that implements [`Error`].
+pub trait IntoOutcome {
+    type Answer: Display;
+
+    fn into_outcome(self) -> Outcome<Self::Answer>;
+}
+
+impl<T: Display> IntoOutcome for Option<T> {
+    type Answer = T;
+
+    fn into_outcome(self) -> Outcome<T> {
+        self.map_or(Outcome::NotImplemented, Outcome::Solved)
+    }
+}
+
+impl<T: Display, E: Into<Box<dyn Error>>> IntoOutcome for Result<T, E> {
+    type Answer = T;
+
+    fn into_outcome(self) -> Outcome<T> {
+        match self {
+            Ok(answer) => Outcome::Solved(answer),
+            Err(e) => Outcome::Failed(error_chain(&*e.into())),
+        }
+    }
+}
+
+/// Formats an error along with its causes, e.g. `invalid card: invalid digit found in string`.
+fn error_chain(error: &dyn Error) -> String {
+    let mut chain = error.to_string();
+    let mut source = error.source();
+
+    while let Some(cause) = source {
+        chain.push_str(": ");
+        chain.push_str(&cause.to_string());
+        source = cause.source();
+    }
+
+    chain
+}
+
+/// Outcomes are passed through, e.g. for [`Variant`](crate::template::variants::Variant)s, which format their answers.
+impl<T: Display> IntoOutcome for Outcome<T> {
+    type Answer = T;
+
+    fn into_outcome(self) -> Outcome<T> {
+        self
+    }
+}
+
+/// A solution of a day. Implementations are wired up with `solution!(<day>, <type>)`.
+///
+/// ```ignore
+/// advent_of_code::solution!(1, Trebuchet);
+///
+/// pub struct Trebuchet;
+///
+/// impl Solution for Trebuchet {
+///     type Input = Vec<String>;
+///     type PartOne = Option<u32>;
+///     type PartTwo = Result<u32, String>;
+///
+///     fn parse(input: &str) -> Self::Input { /* ... */ }
+///     fn part_one(input: &Self::Input) -> Option<u32> { /* ... */ }
+///     fn part_two(input: &Self::Input) -> Result<u32, String> { /* ... */ }
+/// }
+/// ```
+pub trait Solution {
+    /// The parsed puzzle input that both parts work on.
+    type Input;
+    /// Return type of part one, either `Option<T>` or `Result<T, E>`.
+    type PartOne: IntoOutcome;
+    /// Return type of part two, either `Option<T>` or `Result<T, E>`.
+    type PartTwo: IntoOutcome;
+
+    /// Title of the puzzle, printed before the results.
+    #[must_use]
+    fn title() -> Option<&'static str> {
+        None
+    }
+
+    fn parse(input: &str) -> Self::Input;
+
+    fn part_one(input: &Self::Input) -> Self::PartOne;
+
+    fn part_two(input: &Self::Input) -> Self::PartTwo;
+}
+
+/// Parses the input and solves both parts.
+pub fn solve<S: Solution>(input: &str) -> (S::PartOne, S::PartTwo) {
+    let input = S::parse(input);
+    (S::part_one(&input), S::part_two(&input))
+}
+
+/// Solves part one of the example for `day`. Helper for the unit tests of a [`Solution`].
+#[must_use]
+pub fn example_part_one<S: Solution>(day: Day) -> S::PartOne {
+    S::part_one(&S::parse(&read_file("examples", day)))
+}
+
+/// Solves part two of the example for `day`. Helper for the unit tests of a [`Solution`].
+#[must_use]
+pub fn example_part_two<S: Solution>(day: Day) -> S::PartTwo {
+    S::part_two(&S::parse(&read_file("examples", day)))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{error::Error, fmt, num::ParseIntError};
+
+    use super::{solve, IntoOutcome, Outcome, Solution};
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<u32>;
+        type PartOne = Option<u32>;
+        type PartTwo = Option<u32>;
+
+        fn parse(input: &str) -> Self::Input {
+            input.lines().filter_map(|l| l.parse().ok()).collect()
+        }
+
+        fn part_one(input: &Self::Input) -> Option<u32> {
+            Some(input.iter().sum())
+        }
+
+        fn part_two(input: &Self::Input) -> Option<u32> {
+            input.iter().max().copied()
+        }
+    }
+
+    #[test]
+    fn solves_both_parts_from_one_parse() {
+        assert_eq!(solve::<Sum>("1\n5\n2"), (Some(8), Some(5)));
+        assert_eq!(solve::<Sum>(""), (Some(0), None));
+        assert_eq!(Sum::title(), None);
+    }
+
+    #[test]
+    fn distinguishes_failures_from_missing_answers() {
+        assert_eq!(Some(1).into_outcome(), Outcome::Solved(1));
+        assert_eq!(None::<u32>.into_outcome(), Outcome::NotImplemented);
+        assert_eq!(Ok::<u32, String>(2).into_outcome(), Outcome::Solved(2));
+        assert_eq!(
+            Err::<u32, _>("invalid card").into_outcome(),
+            Outcome::Failed("invalid card".into())
+        );
+    }
+
+    #[derive(Debug)]
+    struct InvalidCard(ParseIntError);
+
+    impl fmt::Display for InvalidCard {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "invalid card")
+        }
+    }
+
+    impl Error for InvalidCard {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn formats_chain_of_causes() {
+        let error = InvalidCard("x".parse::<u32>().unwrap_err());
+        assert_eq!(
+            Err::<u32, _>(error).into_outcome(),
+            Outcome::Failed("invalid card: invalid digit found in string".into())
+        );
+    }
+}
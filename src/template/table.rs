@@ -0,0 +1,49 @@
+//! Module that lays out tables for the terminal, e.g. the scaling, growth and variant reports.
+
+/// Pads each column to its widest cell and separates the columns with two spaces.
+/// Returns one line per row, without trailing whitespace.
+#[must_use]
+pub fn format<R: AsRef<[String]>>(rows: &[R]) -> Vec<String> {
+    let columns = rows.iter().map(|r| r.as_ref().len()).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| {
+            rows.iter()
+                .filter_map(|r| r.as_ref().get(i))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    rows.iter()
+        .map(|row| {
+            row.as_ref()
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &width)| format!("{cell:width$}"))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::format;
+
+    #[test]
+    fn pads_columns() {
+        let rows = [
+            vec!["Scale".to_string(), "Part 1".into()],
+            vec!["16".into(), "1.2ms (×2.01)".into()],
+            vec!["Growth".into(), String::new()],
+        ];
+        assert_eq!(
+            format(&rows),
+            ["Scale   Part 1", "16      1.2ms (×2.01)", "Growth"]
+        );
+        assert!(format::<Vec<String>>(&[]).is_empty());
+    }
+}
@@ -0,0 +1,257 @@
+/// Module for alternative implementations of a part, e.g. a naive and an optimized one.
+///
+/// Variants are registered with `solution!(<day>, variants = [(<part>, "<name>", <function>), ...])`.
+/// `cargo solve <day> --variant <name>` runs the named variants instead of `part_one` and `part_two`,
+/// and `cargo solve <day> --compare` benchmarks every variant of each part and checks that they agree.
+use std::{env, fmt::Display, process, time::Duration};
+
+use crate::template::{
+    ocr,
+    solution::{IntoOutcome, Outcome},
+    table, ANSI_ITALIC, ANSI_RESET,
+};
+
+/// Name of the variants that `part_one` and `part_two` are registered as.
+pub const DEFAULT: &str = "default";
+
+/// A part whose answer is formatted, so that variants with different answer types can be stored together.
+type Part = Box<dyn Fn(&str) -> Outcome<String>>;
+
+/// A named implementation of a part.
+pub struct Variant {
+    pub part: u8,
+    pub name: &'static str,
+    func: Part,
+}
+
+impl Variant {
+    pub fn new<R: IntoOutcome>(
+        part: u8,
+        name: &'static str,
+        func: impl Fn(&str) -> R + 'static,
+    ) -> Self {
+        Self {
+            part,
+            name,
+            func: Box::new(move |input| match func(input).into_outcome() {
+                Outcome::Solved(answer) => Outcome::Solved(answer.to_string()),
+                Outcome::NotImplemented => Outcome::NotImplemented,
+                Outcome::Failed(error) => Outcome::Failed(error),
+                Outcome::Panicked(panic) => Outcome::Panicked(panic),
+            }),
+        }
+    }
+
+    pub fn run(&self, input: &str) -> Outcome<String> {
+        (self.func)(input)
+    }
+}
+
+/// Returns the variant passed with `--variant <name>`, if any.
+/// Exits with an error if none of the variants has that name.
+#[must_use]
+pub fn selected(variants: &[Variant]) -> Option<&'static str> {
+    let args: Vec<String> = env::args().collect();
+    let index = args.iter().position(|x| x == "--variant")?;
+
+    let Some(name) = args.get(index + 1) else {
+        eprintln!("`--variant` expects a name.");
+        process::exit(1);
+    };
+
+    if let Some(variant) = variants.iter().find(|v| v.name == name) {
+        return Some(variant.name);
+    }
+
+    if name == DEFAULT {
+        return None;
+    }
+
+    let mut names: Vec<&str> = variants.iter().map(|v| v.name).collect();
+    names.sort_unstable();
+    names.dedup();
+
+    if names.is_empty() {
+        eprintln!("Unknown variant `{name}`. Register variants with `solution!(<day>, variants = [(<part>, \"<name>\", <function>)])`.");
+    } else {
+        eprintln!(
+            "Unknown variant `{name}`, expecting one of: {DEFAULT}, {}.",
+            names.join(", ")
+        );
+    }
+    process::exit(1);
+}
+
+/// Returns the variant with the given name for the part.
+#[must_use]
+pub fn find<'a>(variants: &'a [Variant], part: u8, name: Option<&str>) -> Option<&'a Variant> {
+    let name = name?;
+    variants.iter().find(|v| v.part == part && v.name == name)
+}
+
+/// Prints which of the parts run the selected variant.
+pub fn print_selection(variants: &[Variant], selected: Option<&str>, parts: &[u8]) {
+    let Some(name) = selected else {
+        return;
+    };
+
+    let (with, without): (Vec<u8>, Vec<u8>) = parts
+        .iter()
+        .partition(|&&part| find(variants, part, selected).is_some());
+
+    let format = |parts: Vec<u8>| {
+        parts
+            .iter()
+            .map(u8::to_string)
+            .collect::<Vec<_>>()
+            .join(" and ")
+    };
+
+    if without.is_empty() {
+        println!("{ANSI_ITALIC}Running variant {name}.{ANSI_RESET}");
+    } else {
+        println!(
+            "{ANSI_ITALIC}Running variant {name} of part {}, part {} has no such variant and runs the {DEFAULT}.{ANSI_RESET}",
+            format(with),
+            format(without)
+        );
+    }
+}
+
+/// Whether the binary was invoked with `--compare`.
+#[must_use]
+pub fn compare_requested() -> bool {
+    env::args().any(|x| x == "--compare")
+}
+
+/// Result of running one variant of a part in comparison mode.
+pub struct Comparison {
+    pub name: &'static str,
+    pub outcome: Outcome<String>,
+    /// Mean and standard deviation of the benchmark. Only parts that were solved are benchmarked.
+    pub timing: Option<(Duration, Duration)>,
+}
+
+/// Names of the variants whose outcome differs from the first one, which is the reference.
+#[must_use]
+pub fn disagreeing(comparisons: &[Comparison]) -> Vec<&'static str> {
+    let Some(reference) = comparisons.first() else {
+        return vec![];
+    };
+
+    comparisons
+        .iter()
+        .filter(|c| c.outcome != reference.outcome)
+        .map(|c| c.name)
+        .collect()
+}
+
+/// Formats one line per variant with its result, its time and its speed relative to the first variant.
+/// Results that differ from the first variant's are marked with `✖`.
+#[must_use]
+pub fn format_table(comparisons: &[Comparison]) -> Vec<String> {
+    let Some(reference) = comparisons.first() else {
+        return vec![];
+    };
+
+    let mut rows = vec![["Variant", "Result", "Time", "Speed"].map(String::from)];
+
+    for comparison in comparisons {
+        let mut result = format_outcome(&comparison.outcome);
+        if comparison.outcome != reference.outcome {
+            result += " ✖";
+        }
+
+        let time = comparison.timing.map_or_else(
+            || "-".into(),
+            |(mean, deviation)| format!("{mean:.1?} ± {deviation:.1?}"),
+        );
+
+        let speed = match (reference.timing, comparison.timing) {
+            (Some((reference, _)), Some((mean, _))) if !mean.is_zero() => {
+                format!("×{:.2}", reference.as_secs_f64() / mean.as_secs_f64())
+            }
+            _ => "-".into(),
+        };
+
+        rows.push([comparison.name.to_string(), result, time, speed]);
+    }
+
+    table::format(&rows)
+}
+
+fn format_outcome<T: Display>(outcome: &Outcome<T>) -> String {
+    match outcome {
+        Outcome::Solved(answer) => {
+            let answer = ocr::answer(&answer.to_string());
+            match answer.lines().count() {
+                0 | 1 => answer,
+                lines => format!("▼ {lines} lines"),
+            }
+        }
+        Outcome::NotImplemented => "✖ not implemented".into(),
+        Outcome::Failed(error) => format!("✖ failed: {error}"),
+        Outcome::Panicked(panic) => format!("✖ panicked: {panic}"),
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{disagreeing, find, format_table, Comparison, Variant};
+    use crate::template::solution::Outcome;
+
+    fn comparison(name: &'static str, answer: &str, micros: u64) -> Comparison {
+        Comparison {
+            name,
+            outcome: Outcome::Solved(answer.into()),
+            timing: Some((Duration::from_micros(micros), Duration::from_micros(1))),
+        }
+    }
+
+    #[test]
+    fn runs_variants() {
+        let variants = [
+            Variant::new(1, "fast", |input: &str| Some(input.len())),
+            Variant::new(2, "fast", |input: &str| input.parse::<u32>()),
+        ];
+
+        assert_eq!(
+            find(&variants, 1, Some("fast")).map(|v| v.run("abc")),
+            Some(Outcome::Solved("3".into()))
+        );
+        assert!(matches!(
+            find(&variants, 2, Some("fast")).map(|v| v.run("abc")),
+            Some(Outcome::Failed(_))
+        ));
+        assert!(find(&variants, 1, Some("naive")).is_none());
+        assert!(find(&variants, 1, None).is_none());
+    }
+
+    #[test]
+    fn compares_variants() {
+        let comparisons = [
+            comparison("default", "42", 100),
+            comparison("fast", "42", 25),
+            comparison("broken", "41", 50),
+            Comparison {
+                name: "todo",
+                outcome: Outcome::NotImplemented,
+                timing: None,
+            },
+        ];
+
+        assert_eq!(disagreeing(&comparisons), ["broken", "todo"]);
+        assert_eq!(
+            format_table(&comparisons),
+            [
+                "Variant  Result               Time             Speed",
+                "default  42                   100.0µs ± 1.0µs  ×1.00",
+                "fast     42                   25.0µs ± 1.0µs   ×4.00",
+                "broken   41 ✖                 50.0µs ± 1.0µs   ×2.00",
+                "todo     ✖ not implemented ✖  -                -",
+            ]
+        );
+    }
+}
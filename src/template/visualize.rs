@@ -0,0 +1,483 @@
+/// Module that lets solutions draw frames of their progress, e.g. a grid with the cells that were visited so far.
+///
+/// Frames are only built and drawn if the solution runs with `--visualize`, otherwise [`frame`] returns right away.
+/// They are either animated in the terminal or written as numbered images to the directory passed via `--frames`.
+///
+/// ```ignore
+/// use advent_of_code::template::visualize::{self, Frame};
+///
+/// visualize::frame(|| {
+///     Frame::grid(input.lines())
+///         .highlight(x, y)
+///         .annotate("steps", steps)
+/// });
+/// ```
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Mutex, OnceLock},
+    thread,
+    time::Duration,
+};
+
+use crate::template::{ANSI_BOLD, ANSI_CLEAR_SCREEN, ANSI_RESET, ANSI_YELLOW};
+
+/// Time each frame is shown for in the terminal.
+const FRAME_DELAY: Duration = Duration::from_millis(50);
+
+/// Width and height of a grid cell in images, in pixels.
+const CELL_SIZE: usize = 4;
+
+const BACKGROUND: [u8; 3] = [24, 24, 32];
+const WALL: [u8; 3] = [220, 220, 220];
+const HIGHLIGHT: [u8; 3] = [255, 200, 0];
+/// Colors of cells that are neither empty nor walls, picked by character.
+const PALETTE: [[u8; 3]; 6] = [
+    [86, 156, 214],
+    [78, 201, 176],
+    [197, 134, 192],
+    [206, 145, 120],
+    [181, 206, 168],
+    [244, 71, 71],
+];
+
+/// Image format of the frames written with `--frames`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameFormat {
+    Png,
+    Ppm,
+}
+
+impl FromStr for FrameFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "png" => Ok(Self::Png),
+            "ppm" => Ok(Self::Ppm),
+            _ => Err(format!(
+                "unknown frame format `{s}`, expecting `png` or `ppm`"
+            )),
+        }
+    }
+}
+
+impl FrameFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            FrameFormat::Png => "png",
+            FrameFormat::Ppm => "ppm",
+        }
+    }
+}
+
+/// Where frames are drawn to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Backend {
+    /// Animate the frames in the terminal.
+    Terminal,
+    /// Write each frame as an image to the directory, e.g. `1-00042.png` for the 42nd frame of part one.
+    /// Annotations are appended to `annotations.txt` in the same directory.
+    Frames { dir: PathBuf, format: FrameFormat },
+}
+
+impl Backend {
+    /// Reads the backend from `--visualize`, `--frames <dir>` and `--frame-format <format>`.
+    /// Returns `None` if the solution should not be visualized.
+    pub fn with_args(args: &mut pico_args::Arguments) -> Result<Option<Self>, pico_args::Error> {
+        let terminal = args.contains("--visualize");
+        let dir: Option<PathBuf> = args.opt_value_from_str("--frames")?;
+        let format: Option<FrameFormat> = args.opt_value_from_str("--frame-format")?;
+
+        match (dir, format) {
+            (Some(dir), format) => Ok(Some(Backend::Frames {
+                dir,
+                format: format.unwrap_or(FrameFormat::Png),
+            })),
+            (None, Some(_)) => Err(pico_args::Error::ArgumentParsingFailed {
+                cause: "`--frame-format` requires `--frames <dir>`".into(),
+            }),
+            (None, None) => Ok(terminal.then_some(Backend::Terminal)),
+        }
+    }
+
+    /// Formats the backend as arguments that [`Backend::with_args`] understands.
+    #[must_use]
+    pub fn to_args(&self) -> Vec<String> {
+        match self {
+            Backend::Terminal => vec!["--visualize".into()],
+            Backend::Frames { dir, format } => vec![
+                "--frames".into(),
+                dir.display().to_string(),
+                "--frame-format".into(),
+                format.extension().into(),
+            ],
+        }
+    }
+}
+
+/// A snapshot of the solution's state: a grid of characters with highlighted cells, and key/value annotations.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Frame {
+    rows: Vec<Vec<char>>,
+    highlights: HashSet<(usize, usize)>,
+    annotations: Vec<(String, String)>,
+}
+
+impl Frame {
+    /// A frame without a grid, e.g. for annotations only.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A frame that shows the rows of a grid, e.g. the lines of the input.
+    #[must_use]
+    pub fn grid<R: AsRef<str>>(rows: impl IntoIterator<Item = R>) -> Self {
+        Self {
+            rows: rows
+                .into_iter()
+                .map(|row| row.as_ref().chars().collect())
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    /// A frame that shows a grid of the given size, with the character of each cell at `(x, y)`.
+    #[must_use]
+    pub fn from_cells(width: usize, height: usize, cell: impl Fn(usize, usize) -> char) -> Self {
+        Self {
+            rows: (0..height)
+                .map(|y| (0..width).map(|x| cell(x, y)).collect())
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Highlights the cell in column `x` and row `y`.
+    #[must_use]
+    pub fn highlight(mut self, x: usize, y: usize) -> Self {
+        self.highlights.insert((x, y));
+        self
+    }
+
+    #[must_use]
+    pub fn highlight_all(mut self, cells: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        self.highlights.extend(cells);
+        self
+    }
+
+    #[must_use]
+    pub fn annotate(mut self, key: &str, value: impl Display) -> Self {
+        self.annotations.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    fn is_highlighted(&self, x: usize, y: usize) -> bool {
+        self.highlights.contains(&(x, y))
+    }
+
+    fn width(&self) -> usize {
+        self.rows.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    /// Renders the grid with highlighted cells in bold yellow, followed by the annotations.
+    fn to_text(&self) -> String {
+        let mut text = String::new();
+
+        for (y, row) in self.rows.iter().enumerate() {
+            for (x, c) in row.iter().enumerate() {
+                if self.is_highlighted(x, y) {
+                    text += &format!("{ANSI_YELLOW}{ANSI_BOLD}{c}{ANSI_RESET}");
+                } else {
+                    text.push(*c);
+                }
+            }
+            text.push('\n');
+        }
+
+        for (key, value) in &self.annotations {
+            text += &format!("{key}: {value}\n");
+        }
+
+        text
+    }
+
+    /// Returns the width and height of the image along with its RGB pixels, row by row.
+    fn to_pixels(&self) -> (usize, usize, Vec<u8>) {
+        let width = self.width() * CELL_SIZE;
+        let height = self.rows.len() * CELL_SIZE;
+        let mut pixels = Vec::with_capacity(width * height * 3);
+
+        for y in 0..height {
+            for x in 0..width {
+                let (cell_x, cell_y) = (x / CELL_SIZE, y / CELL_SIZE);
+                let c = self.rows[cell_y].get(cell_x).copied().unwrap_or(' ');
+
+                pixels.extend(if self.is_highlighted(cell_x, cell_y) {
+                    HIGHLIGHT
+                } else {
+                    color(c)
+                });
+            }
+        }
+
+        (width, height, pixels)
+    }
+}
+
+fn color(c: char) -> [u8; 3] {
+    match c {
+        '.' | ' ' => BACKGROUND,
+        '#' | '█' => WALL,
+        c => PALETTE[c as usize % PALETTE.len()],
+    }
+}
+
+struct State {
+    part: u8,
+    frames: usize,
+}
+
+static STATE: Mutex<State> = Mutex::new(State { part: 0, frames: 0 });
+
+/// The backend that the solution was started with, if any.
+fn backend() -> Option<&'static Backend> {
+    static BACKEND: OnceLock<Option<Backend>> = OnceLock::new();
+
+    BACKEND
+        .get_or_init(|| {
+            Backend::with_args(&mut pico_args::Arguments::from_env()).unwrap_or_else(|e| {
+                eprintln!("Invalid visualization arguments: {e}");
+                None
+            })
+        })
+        .as_ref()
+}
+
+/// Whether the solution runs with `--visualize` or `--frames`.
+#[must_use]
+pub fn enabled() -> bool {
+    backend().is_some()
+}
+
+/// Draws the frame that `build` returns. The frame is only built if visualization is enabled.
+pub fn frame(build: impl FnOnce() -> Frame) {
+    let Some(backend) = backend() else {
+        return;
+    };
+
+    let frame = build();
+    let Ok(mut state) = STATE.lock() else {
+        return;
+    };
+    state.frames += 1;
+
+    let result = match backend {
+        Backend::Terminal => {
+            print!(
+                "{ANSI_CLEAR_SCREEN}{ANSI_BOLD}Part {}, frame {}{ANSI_RESET}\n{}",
+                state.part,
+                state.frames,
+                frame.to_text()
+            );
+            let _ = io::stdout().flush();
+            thread::sleep(FRAME_DELAY);
+            Ok(())
+        }
+        Backend::Frames { dir, format } => write_frame(&frame, dir, *format, &state),
+    };
+
+    if let Err(e) = result {
+        eprintln!("Failed to write frame {}: {e}", state.frames);
+    }
+}
+
+/// Starts counting frames for a part. Called by the runner before each part runs.
+pub(crate) fn start_part(part: u8) {
+    if let Ok(mut state) = STATE.lock() {
+        *state = State { part, frames: 0 };
+    }
+}
+
+fn write_frame(frame: &Frame, dir: &Path, format: FrameFormat, state: &State) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let name = format!("{}-{:05}", state.part, state.frames);
+
+    // an image needs at least one pixel, frames without a grid or with empty rows only have annotations.
+    if frame.width() > 0 {
+        let (width, height, pixels) = frame.to_pixels();
+        let image = match format {
+            FrameFormat::Png => encode_png(width, height, &pixels),
+            FrameFormat::Ppm => encode_ppm(width, height, &pixels),
+        };
+        fs::write(dir.join(format!("{name}.{}", format.extension())), image)?;
+    }
+
+    if !frame.annotations.is_empty() {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join("annotations.txt"))?;
+
+        let annotations: Vec<String> = frame
+            .annotations
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect();
+        writeln!(file, "{name} {}", annotations.join(" "))?;
+    }
+
+    Ok(())
+}
+
+fn encode_ppm(width: usize, height: usize, pixels: &[u8]) -> Vec<u8> {
+    let mut image = format!("P6\n{width} {height}\n255\n").into_bytes();
+    image.extend_from_slice(pixels);
+    image
+}
+
+/// Encodes RGB pixels as a PNG. The image data is stored without compression, which avoids a dependency on zlib.
+fn encode_png(width: usize, height: usize, pixels: &[u8]) -> Vec<u8> {
+    // every row starts with the filter type, 0 means unfiltered.
+    let mut raw = Vec::with_capacity(height * (width * 3 + 1));
+    for row in pixels.chunks(width * 3) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    // a zlib stream of uncompressed deflate blocks, which hold up to 65535 bytes each.
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = raw.chunks(0xffff).peekable();
+    while let Some(block) = blocks.next() {
+        #[allow(clippy::cast_possible_truncation)]
+        let len = block.len() as u16;
+        zlib.push(u8::from(blocks.peek().is_none()));
+        zlib.extend(len.to_le_bytes());
+        zlib.extend((!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    if raw.is_empty() {
+        zlib.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    zlib.extend(adler32(&raw).to_be_bytes());
+
+    let mut header = vec![];
+    #[allow(clippy::cast_possible_truncation)]
+    for dimension in [width as u32, height as u32] {
+        header.extend(dimension.to_be_bytes());
+    }
+    // 8 bits per channel, RGB, default compression, filtering and no interlacing.
+    header.extend([8, 2, 0, 0, 0]);
+
+    let mut png = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+    for (kind, data) in [(b"IHDR", header), (b"IDAT", zlib), (b"IEND", vec![])] {
+        #[allow(clippy::cast_possible_truncation)]
+        png.extend((data.len() as u32).to_be_bytes());
+        let start = png.len();
+        png.extend_from_slice(kind);
+        png.extend_from_slice(&data);
+        let crc = crc32(&png[start..]);
+        png.extend(crc.to_be_bytes());
+    }
+
+    png
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + u32::from(byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{env, fs};
+
+    use super::{
+        adler32, crc32, encode_png, encode_ppm, write_frame, Backend, Frame, FrameFormat, State,
+    };
+
+    #[test]
+    fn builds_frames() {
+        let frame = Frame::grid(["#.", ".*"])
+            .highlight(1, 1)
+            .annotate("steps", 3);
+        let (width, height, pixels) = frame.to_pixels();
+
+        assert_eq!((width, height), (8, 8));
+        assert_eq!(pixels.len(), 8 * 8 * 3);
+        assert_eq!(pixels[..3], [220, 220, 220]);
+        assert_eq!(pixels[pixels.len() - 3..], [255, 200, 0]);
+        assert!(frame.to_text().ends_with("steps: 3\n"));
+        assert_eq!(
+            Frame::from_cells(2, 2, |x, y| if x == y { '#' } else { '.' }).rows,
+            Frame::grid(["#.", ".#"]).rows
+        );
+    }
+
+    #[test]
+    fn encodes_images() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+
+        let ppm = encode_ppm(1, 1, &[1, 2, 3]);
+        assert_eq!(ppm, b"P6\n1 1\n255\n\x01\x02\x03");
+
+        let png = encode_png(1, 1, &[1, 2, 3]);
+        assert_eq!(
+            png[..8],
+            [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n']
+        );
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+    }
+
+    #[test]
+    fn skips_images_of_empty_grids() {
+        let dir = env::temp_dir().join(format!("aoc-frames-{}", std::process::id()));
+        let state = State { part: 1, frames: 0 };
+
+        let frame = Frame::grid([""]).annotate("steps", 0);
+        write_frame(&frame, &dir, FrameFormat::Png, &state).unwrap();
+
+        assert!(!dir.join("1-00000.png").exists());
+        assert!(dir.join("annotations.txt").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn round_trips_backend_args() {
+        let backend = Backend::Frames {
+            dir: "target/frames".into(),
+            format: FrameFormat::Ppm,
+        };
+        let mut args =
+            pico_args::Arguments::from_vec(backend.to_args().iter().map(Into::into).collect());
+        assert_eq!(Backend::with_args(&mut args).unwrap(), Some(backend));
+
+        let mut args = pico_args::Arguments::from_vec(vec!["--frame-format".into(), "png".into()]);
+        assert!(Backend::with_args(&mut args).is_err());
+    }
+}